@@ -0,0 +1,92 @@
+//! Validation of IPFS content identifiers referenced from campaign metadata.
+//!
+//! Campaigns can keep their long-form content off chain and only store its CID, a hash of the
+//! content and its MIME type. The CID is stored in its string form, which is checked here so
+//! clients never resolve malformed identifiers:
+//!
+//! * CIDv0: 46 base58btc characters starting with `Qm`, the encoding of a sha2-256 multihash.
+//! * CIDv1: multibase prefix `b` followed by lowercase RFC 4648 base32 without padding, decoding to
+//!   version `1`, a content codec and a multihash whose digest length matches its header.
+
+use alloc::vec::Vec;
+
+/// Upper bound on the length of a CID string.
+pub const MAX_CID_LEN: u32 = 128;
+
+/// Upper bound on the length of a MIME type.
+pub const MAX_MIME_TYPE_LEN: u32 = 64;
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Whether `cid` is a well-formed CIDv0 or CIDv1 string.
+pub fn is_valid(cid: &[u8]) -> bool {
+    match cid {
+        [b'Q', b'm', ..] => is_valid_v0(cid),
+        [b'b', encoded @ ..] => base32_decode(encoded).is_some_and(|bytes| is_valid_v1(&bytes)),
+        _ => false,
+    }
+}
+
+/// Whether `mime_type` looks like `type/subtype`, both made of RFC 6838 name characters.
+pub fn is_valid_mime_type(mime_type: &[u8]) -> bool {
+    let is_name = |part: &[u8]| {
+        !part.is_empty() &&
+            part[0].is_ascii_alphanumeric() &&
+            part.iter().all(|c| c.is_ascii_alphanumeric() || b"!#$&-^_.+".contains(c))
+    };
+    let mut parts = mime_type.splitn(2, |c| *c == b'/');
+    match (parts.next(), parts.next()) {
+        (Some(kind), Some(subtype)) => is_name(kind) && is_name(subtype),
+        _ => false,
+    }
+}
+
+fn is_valid_v0(cid: &[u8]) -> bool {
+    cid.len() == 46 && cid.iter().all(|c| BASE58_ALPHABET.contains(c))
+}
+
+fn is_valid_v1(mut bytes: &[u8]) -> bool {
+    let version = read_varint(&mut bytes);
+    let codec = read_varint(&mut bytes);
+    let hash_code = read_varint(&mut bytes);
+    let digest_len = read_varint(&mut bytes);
+    match (version, codec, hash_code, digest_len) {
+        (Some(1), Some(_), Some(_), Some(len)) => len > 0 && len == bytes.len() as u64,
+        _ => false,
+    }
+}
+
+/// Read an unsigned varint as used by multiformats, at most 9 bytes long.
+fn read_varint(bytes: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for (i, byte) in bytes.iter().enumerate().take(9) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            *bytes = &bytes[i + 1..];
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Decode lowercase, unpadded RFC 4648 base32.
+fn base32_decode(encoded: &[u8]) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(encoded.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for c in encoded {
+        let value = match c {
+            b'a'..=b'z' => c - b'a',
+            b'2'..=b'7' => c - b'2' + 26,
+            _ => return None,
+        };
+        buffer = (buffer << 5) | u32::from(value);
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    // Leftover bits are padding: fewer than a character's worth, all zero
+    (bits < 5 && buffer == 0).then_some(bytes)
+}
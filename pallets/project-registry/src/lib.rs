@@ -14,78 +14,349 @@
 //! ## Terminology
 //! 
 //! * **Campaign**: A funding initiative with metadata, time bounds, and funding targets.
-//! * **Soft Cap**: Minimum funding goal that must be met for the campaign to succeed.
+//! * **Soft Cap**: Funding goal of the campaign.
+//! * **Funding Model**: Whether a campaign below its soft cap fails and refunds every
+//!   contribution (`AllOrNothing`), pays out whatever it raised (`KeepItAll`) or pays out once
+//!   it reaches a share of the soft cap (`Threshold`).
 //! * **Hard Cap**: Maximum funding that a campaign can accept.
-//! * **Deposit**: Required stake from campaign creators to prevent spam.
-//! * **Metadata**: Campaign information including name, description, and optional link.
+//! * **Stretch Goal**: A milestone above the soft cap. Reaching one lets the owner raise the hard
+//!   cap to the next goal's target.
+//! * **Deposit**: Required stake from campaign creators to prevent spam, a base plus an amount
+//!   proportional to the encoded size of the metadata, adjusted whenever the metadata changes.
+//! * **Success Fee**: Share of a successful campaign's raise sent to `OnSuccessFee`, usually the
+//!   treasury.
+//! * **Parameters**: Minimum contribution and matching defaults kept in storage so
+//!   governance can tune them without a runtime upgrade.
+//! * **Metadata**: Campaign information including name, description, optional link, category and
+//!   tags. Campaigns are indexed by category, tag and owner so the
+//!   [`runtime_api::ProjectRegistryApi`] can list them without scanning every campaign.
+//!   Long-form content can be kept on IPFS and referenced by a CID checked by the [`cid`]
+//!   module, with the content hash and MIME type. Names must be printable UTF-8 and links
+//!   `https://` or `ipfs://` URIs (see the [`link`] module), and runtimes can add rules through a
+//!   [`MetadataValidator`].
+//! * **Reward Tier**: A reward promised to contributors of at least a minimum amount, optionally
+//!   limited to a number of backers. Owners export who selected which tier through the
+//!   [`runtime_api::ProjectRegistryApi`].
+//! * **Round**: A quadratic funding period with a matching pool shared by approved campaigns.
+//!   Campaigns end within their round, and every contribution they received counts toward the
+//!   match, including those made before the round started.
+//! * **Curator**: The `RoundCuratorOrigin`, or an account appointed for a single round, deciding
+//!   which applications take part in the round.
+//! * **Private Round**: A round with a reveal window. Its approved campaigns only take sealed
+//!   contributions: contributors commit to `hash(amount, salt)` and reserve an upper bound while
+//!   the campaign is active, then reveal the amount within the window after its end. Only
+//!   revealed amounts count toward the campaign and its match, and campaigns are finalized once
+//!   the window closes. Campaigns applying to a private round must not have any contributions
+//!   yet and take none while their application is pending.
+//! * **Anti-Collusion Round**: A round with a coordinator. Contributors sign up with a budget and
+//!   allocate it with messages only the coordinator can decrypt, then the coordinator posts the
+//!   per-campaign tally with a proof checked by `TallyVerifier`. See the [`tally`] module.
+//! * **Receipt**: Whatever the runtime's `OnContribution` hook issues for a contribution, such as
+//!   a soulbound NFT.
+//! * **Challenge**: A bonded fraud report against a campaign. While open, the campaign is not
+//!   eligible for matching funds.
+//! * **Admin**: The `AdminOrigin` creating rounds, appointing curators and cancelling campaigns.
+//!   Runtimes are expected to route it through on-chain governance rather than a sudo key.
 //! 
 //! ## Campaign Lifecycle
 //! 
 //! 1. **Creation**: Owner creates campaign with metadata and funding goals
 //! 2. **Upcoming**: Campaign is created but not yet started
-//! 3. **Active**: Campaign is accepting contributions
-//! 4. **Finalization**: Campaign ends and is marked as Success/Failed
+//! 3. **Active**: Campaign is accepting contributions. The owner can pause it, halting
+//!    contributions and finalization until it is resumed, at the latest after `MaxPauseDuration`.
+//! 4. **Finalization**: Campaign ends and is marked as Success/Failed according to its funding model
 //! 5. **Refund**: Contributors can claim refunds if campaign failed
+//! 6. **Settlement**: Anyone settles the contributions in batches with `settle_batch`, paying
+//!    them to the owner on success or back to contributors otherwise, for a small bounty taken
+//!    from the deposit. The campaign becomes `Settled` once every contribution is processed.
+//!    Successful campaigns are only paid out after `PayoutDelay`, during which governance can
+//!    still force-cancel them.
+//! 7. **Reaping**: After `RetentionPeriod`, anyone can remove the campaign and its contributions
+//!    with `reap_campaign`. The rest of the deposit returns to the owner and a summary is kept in
+//!    `CampaignHistory`.
 //! 
 //! ## Interface
 //! 
 //! ### Dispatchable Functions
 //! 
-//! * `create_campaign` - Create a new funding campaign
-//! * `update_metadata` - Update campaign metadata (only before start)
+//! * `create_campaign` - Create a new funding campaign with optional reward tiers
+//! * `update_metadata` - Update campaign metadata, logging a revision (only the description once
+//!   started)
 //! * `set_caps` - Modify funding caps (only before start)
-//! * `cancel_campaign` - Cancel a campaign (owner or root only)
-//! * `contribute` - Contribute funds to an active campaign
+//! * `unlock_stretch_goal` - Raise the hard cap to the next stretch goal once the previous one is
+//!   reached
+//! * `extend_campaign` - Push the end of an active campaign out, up to `MaxExtension` in total
+//! * `set_auto_extension` - Set the window in which a contribution pushes the end out
+//! * `pause_campaign` / `resume_campaign` - Halt and resume contributions to an active campaign
+//! * `cancel_campaign` - Cancel a campaign (owner or `AdminOrigin`)
+//! * `force_cancel_campaign` - Cancel a campaign in any state, optionally slashing the deposit
+//!   (`AdminOrigin` only)
+//! * `contribute` - Contribute funds to an active campaign, optionally selecting a reward tier
+//!   and accepting only the headroom left under the hard cap
+//! * `commit_contribution` - Commit to a sealed contribution to a campaign of a private round
+//! * `reveal_contribution` - Reveal a sealed contribution after the campaign's end
+//! * `withdraw_commitment` - Release a commitment that was not revealed
 //! * `claim_refund` - Claim refund from failed/cancelled campaigns
+//! * `settle_batch` - Pay out or refund the next batch of contributions of a finished campaign
+//! * `reap_campaign` - Remove a settled campaign from storage after the retention period
+//! * `create_round` - Open a quadratic funding round (`AdminOrigin` only)
+//! * `set_round_curator` - Appoint an account curating a round (`AdminOrigin` only)
+//! * `fund_round` - Add funds to a round's matching pool
+//! * `fund_round_from_treasury` - Fund a round's matching pool from the treasury
+//!   (`TreasurySpendOrigin` only, up to its limit per `TreasurySpendPeriod`)
+//! * `apply_to_round` - Apply with a campaign to a round
+//! * `approve_application` / `reject_application` - Curate round applications
+//! * `set_round_coordinator` - Make a round an anti-collusion round (`AdminOrigin` only)
+//! * `signup` - Sign up to an anti-collusion round with a key and a budget
+//! * `publish_message` - Publish an encrypted allocation message to an anti-collusion round
+//! * `submit_tally` - Post the coordinator's tally and its proof
+//! * `finalize_round` - Distribute the matching pool once the round has ended, refunding unspent
+//!   signup budgets and returning what is left to the treasury
+//! * `set_parameters` - Update the registry parameters (`ParameterOrigin` only)
+//! * `challenge_campaign` - Flag a campaign as fraudulent by posting a challenge bond
+//! * `resolve_challenge` - Uphold or dismiss a challenge (`AdminOrigin` only)
+//! 
+//! ## Invariants
+//! 
+//! `do_try_state` checks the registry accounting. It runs in the `try_state` hook under the
+//! `try-runtime` feature and after every unit test.
+//! 
+//! ## Migrations
+//! 
+//! The pallet declares a `StorageVersion`. Layout changes ship with a multi-block migration in
+//! the `migrations` module, which runtimes hand to `pallet-migrations`. The hooks stay idle
+//! until it has run.
 //! 
 //! ## Security
 //! 
 //! The pallet implements several security measures:
 //! 
 //! 1. Required deposits for campaign creation
-//! 2. Time-bound operations (updates only before start)
-//! 3. Owner-only campaign management
+//! 2. Time-bound operations (updates only before start, apart from logged description edits)
+//! 3. Owner-only campaign management, with governance-controlled administration
 //! 4. Fund reservation for contributions
 //! 5. Automatic campaign finalization
 //! 6. Safe math operations using `saturating_*` methods
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
+use alloc::vec::Vec;
+use frame::deps::{frame_support, frame_system, sp_runtime};
 use frame_support::{
     pallet_prelude::*,
-    traits::{Currency, ReservableCurrency, Get},
+    traits::{
+        BalanceStatus, Currency, ReservableCurrency, Get, ExistenceRequirement, Imbalance,
+        OnUnbalanced, Time,
+    },
     BoundedVec,
+    PalletId,
 };
 use frame_system::pallet_prelude::*;
-use sp_runtime::traits::{Zero, AtLeast32BitUnsigned};
-use sp_std::prelude::*;
+use sp_runtime::{
+    traits::{Zero, AccountIdConversion, IntegerSquareRoot, Saturating, Hash},
+    Perbill, Permill,
+};
+
+pub use pallet::*;
+
+pub mod cid;
+pub mod link;
+pub mod migrations;
+pub mod runtime_api;
+pub mod tally;
+
+use tally::{TallyEntry, TallyInputs, TallyVerifier};
+
+/// Hook run after every successful contribution, e.g. to issue a receipt to the contributor.
+///
+/// It cannot fail the contribution, so implementations are expected to roll back and swallow
+/// their own errors.
+pub trait OnContribution<AccountId, Balance> {
+    /// `who` contributed to `campaign_id`, which applied to `round_id`, and has contributed
+    /// `total` to it so far.
+    fn on_contribution(
+        campaign_id: CampaignId,
+        round_id: Option<RoundId>,
+        who: &AccountId,
+        total: Balance,
+    );
+
+    /// Upper bound of the weight of `on_contribution`, charged with every contribution.
+    fn weight() -> Weight;
+}
+
+impl<AccountId, Balance> OnContribution<AccountId, Balance> for () {
+    fn on_contribution(_: CampaignId, _: Option<RoundId>, _: &AccountId, _: Balance) {}
+
+    fn weight() -> Weight {
+        Weight::zero()
+    }
+}
+
+/// Runtime-specific checks on campaign metadata, run by `create_campaign` and `update_metadata`
+/// after the pallet's own validation.
+pub trait MetadataValidator<Metadata> {
+    /// Reject `metadata` with an error of the runtime's choosing.
+    fn validate(metadata: &Metadata) -> DispatchResult;
+}
+
+impl<Metadata> MetadataValidator<Metadata> for () {
+    fn validate(_: &Metadata) -> DispatchResult {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
 
 #[frame_support::pallet]
+#[allow(clippy::too_many_arguments)]
 pub mod pallet {
     use super::*;
 
+    /// The in-code storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
     pub type CampaignId = u32;
+    pub type RoundId = u32;
+    pub type TierId = u32;
+    pub type TagId = u32;
+
+    /// Field a campaign is listed under.
+    #[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum Category {
+        Infrastructure,
+        Tooling,
+        Education,
+        Community,
+        Research,
+        Art,
+        Other,
+    }
+
+    /// Upper bound on the length of a raw storage key kept as a settlement cursor.
+    pub const MAX_CURSOR_LEN: u32 = 256;
+    pub type CursorOf = BoundedVec<u8, ConstU32<MAX_CURSOR_LEN>>;
     pub type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+    pub type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
     pub type MomentOf<T> = <<T as Config>::Timestamp as frame_support::traits::Time>::Moment;
+    pub type PublicKeyOf<T> = <<T as Config>::TallyVerifier as TallyVerifier<
+        <T as frame_system::Config>::Hash,
+        BalanceOf<T>,
+    >>::PublicKey;
+    pub type ProofOf<T> = <<T as Config>::TallyVerifier as TallyVerifier<
+        <T as frame_system::Config>::Hash,
+        BalanceOf<T>,
+    >>::Proof;
+    pub type MessageOf<T> = BoundedVec<u8, <T as Config>::MaxMessageLen>;
 
-    #[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[derive(
+        CloneNoBound, Encode, Decode, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo,
+        MaxEncodedLen,
+    )]
     #[scale_info(skip_type_params(T))]
     pub struct Metadata<T: Config> {
         pub name: BoundedVec<u8, T::MaxNameLen>,
         pub description: BoundedVec<u8, T::MaxDescLen>,
         pub link: Option<BoundedVec<u8, T::MaxLinkLen>>,
+        pub category: Category,
+        /// Distinct tag ids
+        pub tags: BoundedVec<TagId, T::MaxTags>,
+        /// Long-form content kept off chain
+        pub content: Option<ContentRef<T>>,
+    }
+
+    /// Entry of a campaign's metadata revision log.
+    #[derive(
+        CloneNoBound, Encode, Decode, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    pub struct Revision<T: Config> {
+        /// Increases by one with every update, starting at 0 with the first logged metadata
+        pub number: u32,
+        pub block: BlockNumberFor<T>,
+        /// Hash of the encoded metadata
+        pub hash: T::Hash,
+    }
+
+    pub type RevisionsOf<T> = BoundedVec<Revision<T>, <T as Config>::MaxRevisions>;
+
+    /// Reference to campaign content stored on IPFS.
+    #[derive(
+        CloneNoBound, Encode, Decode, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    pub struct ContentRef<T: Config> {
+        /// CIDv0 or CIDv1 string, checked by [`cid::is_valid`]
+        pub cid: BoundedVec<u8, ConstU32<{ cid::MAX_CID_LEN }>>,
+        /// Hash of the content, so clients can check what a gateway serves
+        pub hash: T::Hash,
+        /// MIME type of the content, e.g. `text/markdown`
+        pub mime_type: BoundedVec<u8, ConstU32<{ cid::MAX_MIME_TYPE_LEN }>>,
     }
 
-    #[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    /// Reward promised to contributors of at least `min_amount`.
+    #[derive(
+        CloneNoBound, Encode, Decode, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    pub struct RewardTier<T: Config> {
+        pub min_amount: BalanceOf<T>,
+        pub description: BoundedVec<u8, T::MaxDescLen>,
+        /// Maximum number of backers, unlimited if `None`
+        pub supply: Option<u32>,
+    }
+
+    pub type TiersOf<T> = BoundedVec<RewardTier<T>, <T as Config>::MaxTiers>;
+
+    /// Milestone above the soft cap. Reaching it lets the owner raise the hard cap to the next one.
+    #[derive(
+        CloneNoBound, Encode, Decode, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    pub struct StretchGoal<T: Config> {
+        pub target: BalanceOf<T>,
+        pub description: BoundedVec<u8, T::MaxDescLen>,
+    }
+
+    pub type StretchGoalsOf<T> = BoundedVec<StretchGoal<T>, <T as Config>::MaxStretchGoals>;
+
+    #[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
     pub enum CampaignStatus {
         Upcoming,
         Active,
         Success,
         Failed,
         Cancelled,
+        Settled,
+        /// Halted by the owner, neither accepting contributions nor finalizing
+        Paused,
+    }
+
+    /// Whether a campaign pays out what it raised when it ends.
+    #[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum FundingModel {
+        /// Succeeds only once the soft cap is reached, otherwise every contribution is refunded
+        AllOrNothing,
+        /// Succeeds with whatever was raised
+        KeepItAll,
+        /// Succeeds once the raise reaches this share of the soft cap, which must not be zero
+        Threshold(Perbill),
     }
 
-    #[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[derive(
+        CloneNoBound, Encode, Decode, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo,
+        MaxEncodedLen,
+    )]
     #[scale_info(skip_type_params(T))]
     pub struct Campaign<T: Config> {
         pub owner: T::AccountId,
@@ -96,8 +367,128 @@ pub mod pallet {
         pub hard_cap: BalanceOf<T>,
         pub matched: BalanceOf<T>,
         pub status: CampaignStatus,
+        pub deposit: BalanceOf<T>,
+        pub contributors: u32,
+        pub funding_model: FundingModel,
+    }
+
+    /// Compact record of a reaped campaign.
+    #[derive(
+        CloneNoBound, Encode, Decode, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    pub struct CampaignSummary<T: Config> {
+        pub owner: T::AccountId,
+        pub raised: BalanceOf<T>,
+        pub contributors: u32,
+        pub matching: BalanceOf<T>,
+        pub settled_at: MomentOf<T>,
+    }
+
+    #[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum RoundStatus {
+        Open,
+        Finalized,
+    }
+
+    #[derive(
+        CloneNoBound, Encode, Decode, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    pub struct Round<T: Config> {
+        pub start: MomentOf<T>,
+        pub end: MomentOf<T>,
+        pub matching_pool: BalanceOf<T>,
+        pub match_cap: Perbill,
+        pub curator: Option<T::AccountId>,
+        pub status: RoundStatus,
+        /// Set for private rounds: how long after its end each campaign of the round accepts
+        /// reveals of sealed contributions
+        pub reveal_window: Option<MomentOf<T>>,
+    }
+
+    /// Coordinator and hash chains of an anti-collusion round.
+    #[derive(
+        CloneNoBound, Encode, Decode, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    pub struct Coordination<T: Config> {
+        /// Account submitting the tally
+        pub coordinator: T::AccountId,
+        /// Key contributors encrypt their messages to
+        pub public_key: PublicKeyOf<T>,
+        pub signups: u32,
+        pub signup_chain: T::Hash,
+        pub messages: u32,
+        pub message_chain: T::Hash,
+        /// Budget of all signups, held in the pallet account
+        pub budget: BalanceOf<T>,
+    }
+
+    #[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum ApplicationStatus<Hash> {
+        Pending,
+        Approved,
+        Rejected { reason: Hash },
+    }
+
+    /// Sealed contribution to a campaign of a private round.
+    #[derive(
+        CloneNoBound, Encode, Decode, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    pub struct Commitment<T: Config> {
+        /// `T::Hashing` of the SCALE-encoded `(amount, salt)`
+        pub hash: T::Hash,
+        /// Upper bound on the amount, reserved from the contributor until the reveal
+        pub bound: BalanceOf<T>,
+    }
+
+    #[derive(
+        CloneNoBound, Encode, Decode, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    pub struct Challenge<T: Config> {
+        pub challenger: T::AccountId,
+        pub bond: BalanceOf<T>,
+        pub evidence: T::Hash,
+    }
+
+    /// Registry parameters adjustable by `ParameterOrigin`.
+    #[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct RegistryParameters<Balance> {
+        /// Smallest contribution a campaign accepts
+        pub min_contribution: Balance,
+        /// Largest share of a round's matching pool one campaign can receive,
+        /// copied into each round when it is created
+        pub match_cap: Perbill,
+        /// Bond reserved from the challenger of a campaign
+        pub challenge_bond: Balance,
+        /// Share of a successful campaign's raise charged when it is settled
+        pub success_fee: Permill,
+        /// Paid from the owner's deposit to the caller of `settle_batch` per settled contribution
+        pub settlement_bounty: Balance,
+    }
+
+    impl<Balance: Default> Default for RegistryParameters<Balance> {
+        fn default() -> Self {
+            Self {
+                min_contribution: Default::default(),
+                match_cap: Perbill::one(),
+                challenge_bond: Default::default(),
+                success_fee: Permill::zero(),
+                settlement_bounty: Default::default(),
+            }
+        }
     }
 
+    pub type ParametersOf<T> = RegistryParameters<BalanceOf<T>>;
+
     #[pallet::config]
     pub trait Config: frame_system::Config {
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
@@ -124,12 +515,115 @@ pub mod pallet {
         #[pallet::constant]
         type MaxActive: Get<u32>;
 
-        /// Minimum deposit required to create a campaign
+        /// Origin allowed to update the registry parameters
+        type ParameterOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Origin allowed to create rounds, appoint curators and cancel any campaign
+        type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Handler for deposits and challenge bonds slashed by governance
+        type Slashed: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+        /// Handler for the fee charged on successful campaigns
+        type OnSuccessFee: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+        /// Account treasury-funded matching pools are paid from and unallocated matching funds
+        /// are returned to
+        type TreasuryAccount: Get<Self::AccountId>;
+
+        /// Origin allowed to fund rounds from the treasury, returning the maximum amount
+        type TreasurySpendOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = BalanceOf<Self>>;
+
+        /// Period the `TreasurySpendOrigin` limit applies to, counting everything spent in it
+        #[pallet::constant]
+        type TreasurySpendPeriod: Get<MomentOf<Self>>;
+
+        /// Origin allowed to approve or reject round applications
+        type RoundCuratorOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Identifier of the account holding round matching pools
+        #[pallet::constant]
+        type PalletId: Get<PalletId>;
+
+        /// How long before a round starts that applications close
+        #[pallet::constant]
+        type ApplicationCutoff: Get<MomentOf<Self>>;
+
+        /// Maximum number of approved campaigns in a round
+        #[pallet::constant]
+        type MaxRoundCampaigns: Get<u32>;
+
+        /// How long a settled campaign is kept before it can be reaped
+        #[pallet::constant]
+        type RetentionPeriod: Get<MomentOf<Self>>;
+
+        /// Maximum number of contributions removed by one `reap_campaign` call
+        #[pallet::constant]
+        type MaxReapBatch: Get<u32>;
+
+        /// Verifier of coordinator tallies in anti-collusion rounds
+        type TallyVerifier: TallyVerifier<Self::Hash, BalanceOf<Self>>;
+
+        /// Maximum length of an encrypted anti-collusion message
+        #[pallet::constant]
+        type MaxMessageLen: Get<u32>;
+
+        /// How long after its end an anti-collusion round waits for the tally before it can be
+        /// finalized without one
+        #[pallet::constant]
+        type TallyTimeout: Get<MomentOf<Self>>;
+
+        /// Maximum number of signups per anti-collusion round
+        #[pallet::constant]
+        type MaxSignups: Get<u32>;
+
+        /// Hook run after each contribution, e.g. to issue receipts
+        type OnContribution: OnContribution<Self::AccountId, BalanceOf<Self>>;
+
+        /// Maximum number of reward tiers per campaign
+        #[pallet::constant]
+        type MaxTiers: Get<u32>;
+
+        /// Maximum number of stretch goals per campaign
+        #[pallet::constant]
+        type MaxStretchGoals: Get<u32>;
+
+        /// How far a campaign's end can be pushed out in total, by its owner, by auto-extension or
+        /// when resuming a paused campaign
+        #[pallet::constant]
+        type MaxExtension: Get<MomentOf<Self>>;
+
+        /// How long a campaign can stay paused before it resumes on its own
+        #[pallet::constant]
+        type MaxPauseDuration: Get<MomentOf<Self>>;
+
+        /// Maximum number of tags per campaign
+        #[pallet::constant]
+        type MaxTags: Get<u32>;
+
+        /// Deposit reserved from the owner of every campaign, on top of its metadata bytes
+        #[pallet::constant]
+        type DepositBase: Get<BalanceOf<Self>>;
+
+        /// Deposit reserved per byte of encoded campaign metadata
         #[pallet::constant]
-        type MinimumDeposit: Get<BalanceOf<Self>>;
+        type DepositPerByte: Get<BalanceOf<Self>>;
+
+        /// Number of metadata revisions kept per campaign; older revisions are dropped first
+        #[pallet::constant]
+        type MaxRevisions: Get<u32>;
+
+        /// Further checks on campaign metadata
+        type MetadataValidator: MetadataValidator<Metadata<Self>>;
+
+        /// How long after a successful campaign is finalized its contributions are held before
+        /// they can be paid out, giving governance time to force-cancel it
+        #[pallet::constant]
+        type PayoutDelay: Get<MomentOf<Self>>;
     }
 
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     #[pallet::storage]
@@ -161,305 +655,2623 @@ pub mod pallet {
         ValueQuery,
     >;
 
-    #[pallet::event]
-    #[pallet::generate_deposit(pub(super) fn deposit_event)]
-    pub enum Event<T: Config> {
-        /// Campaign created. [campaign_id, owner]
-        CampaignCreated { campaign_id: CampaignId, owner: T::AccountId },
-        /// Campaign metadata updated. [campaign_id]
-        MetadataUpdated { campaign_id: CampaignId },
-        /// Campaign caps updated. [campaign_id, soft_cap, hard_cap]
-        CapsUpdated { campaign_id: CampaignId, soft_cap: BalanceOf<T>, hard_cap: BalanceOf<T> },
-        /// Campaign cancelled. [campaign_id]
-        CampaignCancelled { campaign_id: CampaignId },
-        /// Contribution made to campaign. [campaign_id, who, amount]
-        ContributionMade { campaign_id: CampaignId, who: T::AccountId, amount: BalanceOf<T> },
-        /// Campaign finalized. [campaign_id, status]
-        CampaignFinalized { campaign_id: CampaignId, status: CampaignStatus },
-        /// Refund claimed. [campaign_id, who, amount]
-        RefundClaimed { campaign_id: CampaignId, who: T::AccountId, amount: BalanceOf<T> },
-    }
+    /// Sum of the square roots of each contribution to a campaign, the basis of its quadratic
+    /// match. Kept up to date as contributions are made so matching never iterates contributors.
+    #[pallet::storage]
+    pub type CampaignRootSums<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        CampaignId,
+        BalanceOf<T>,
+        ValueQuery,
+    >;
 
-    #[pallet::error]
-    pub enum Error<T> {
-        /// Campaign not found
-        CampaignNotFound,
-        /// Not the campaign owner
-        NotOwner,
-        /// Invalid time range specified
-        InvalidTimeRange,
-        /// Invalid cap values
-        CapsInvalid,
-        /// Campaign is not active
-        NotActive,
-        /// Campaign hard cap would be exceeded
-        HardCapExceeded,
-        /// Campaign already finalized
-        AlreadyFinalized,
-        /// No contribution found to refund
-        NoContributionFound,
-        /// Maximum number of active campaigns reached
-        TooManyActiveCampaigns,
-        /// Campaign has not failed or been cancelled
-        NotRefundable,
-    }
+    /// Contributions refunded through `claim_refund`. They leave `CampaignContributions` but stay
+    /// in `matched`, which keeps recording what the campaign raised.
+    #[pallet::storage]
+    pub type CampaignRefunds<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        CampaignId,
+        BalanceOf<T>,
+        ValueQuery,
+    >;
 
-    #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-        fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
-            let mut weight = Weight::zero();
-            let now = T::Timestamp::now();
-            
-            let active = ActiveCampaigns::<T>::get();
-            let mut updated = active.clone();
-            
-            for campaign_id in active.iter() {
-                weight = weight.saturating_add(T::DbWeight::get().reads(1));
-                
-                if let Some(mut campaign) = Campaigns::<T>::get(campaign_id) {
-                    if campaign.status == CampaignStatus::Active && now >= campaign.end {
-                        // Finalize campaign
-                        campaign.status = if campaign.matched >= campaign.soft_cap {
-                            CampaignStatus::Success
-                        } else {
-                            CampaignStatus::Failed
-                        };
-                        
-                        Campaigns::<T>::insert(campaign_id, campaign.clone());
-                        updated.retain(|id| id != campaign_id);
-                        
-                        Self::deposit_event(Event::CampaignFinalized {
-                            campaign_id: *campaign_id,
-                            status: campaign.status,
-                        });
-                        
-                        weight = weight.saturating_add(T::DbWeight::get().writes(1));
-                    }
-                }
-            }
-            
-            if updated != active {
-                ActiveCampaigns::<T>::put(updated);
-                weight = weight.saturating_add(T::DbWeight::get().writes(1));
-            }
-            
-            weight
-        }
-    }
+    #[pallet::storage]
+    pub type Parameters<T: Config> = StorageValue<_, ParametersOf<T>, ValueQuery>;
 
-    #[pallet::call]
-    impl<T: Config> Pallet<T> {
-        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 2))]
-        pub fn create_campaign(
-            origin: OriginFor<T>,
-            metadata: Metadata<T>,
-            start: MomentOf<T>,
-            end: MomentOf<T>,
-            soft_cap: BalanceOf<T>,
-            hard_cap: BalanceOf<T>,
-        ) -> DispatchResult {
-            let owner = ensure_signed(origin)?;
-            
-            ensure!(start < end, Error::<T>::InvalidTimeRange);
-            ensure!(soft_cap <= hard_cap, Error::<T>::CapsInvalid);
-            ensure!(
-                !soft_cap.is_zero() && !hard_cap.is_zero(),
-                Error::<T>::CapsInvalid
-            );
-            
-            let now = T::Timestamp::now();
-            let status = if now < start {
-                CampaignStatus::Upcoming
-            } else if now <= end {
-                CampaignStatus::Active
-            } else {
-                return Err(Error::<T>::InvalidTimeRange.into());
-            };
-            
-            // Reserve the deposit
-            T::Currency::reserve(&owner, T::MinimumDeposit::get())?;
-            
-            let campaign_id = NextCampaignId::<T>::get();
-            let campaign = Campaign {
-                owner: owner.clone(),
-                metadata,
-                start,
-                end,
-                soft_cap,
-                hard_cap,
-                matched: Zero::zero(),
-                status,
-            };
-            
-            Campaigns::<T>::insert(campaign_id, campaign);
-            NextCampaignId::<T>::put(campaign_id.saturating_add(1));
-            
-            if status == CampaignStatus::Active {
-                ActiveCampaigns::<T>::try_mutate(|campaigns| {
-                    campaigns.try_push(campaign_id)
+    /// Raw key of the last contribution settled by `settle_batch`, for campaigns whose
+    /// settlement is in progress.
+    #[pallet::storage]
+    pub type SettlementCursors<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        CampaignId,
+        CursorOf,
+    >;
+
+    /// When each settled campaign finished settlement.
+    #[pallet::storage]
+    pub type SettledAt<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        CampaignId,
+        MomentOf<T>,
+    >;
+
+    /// `clear_prefix` cursor of campaigns whose contributions are being reaped.
+    #[pallet::storage]
+    pub type ReapCursors<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        CampaignId,
+        CursorOf,
+    >;
+
+    /// Summaries of reaped campaigns.
+    #[pallet::storage]
+    pub type CampaignHistory<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        CampaignId,
+        CampaignSummary<T>,
+    >;
+
+    /// Unrevealed sealed contributions to campaigns of private rounds.
+    #[pallet::storage]
+    pub type Commitments<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        CampaignId,
+        Blake2_128Concat,
+        T::AccountId,
+        Commitment<T>,
+    >;
+
+    /// Reward tiers of each campaign, indexed by `TierId`.
+    #[pallet::storage]
+    pub type CampaignTiers<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        CampaignId,
+        TiersOf<T>,
+        ValueQuery,
+    >;
+
+    /// Number of contributors who selected each reward tier.
+    #[pallet::storage]
+    pub type TierBackers<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        CampaignId,
+        Twox64Concat,
+        TierId,
+        u32,
+        ValueQuery,
+    >;
+
+    /// Reward tier selected by each contributor.
+    #[pallet::storage]
+    pub type TierSelections<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        CampaignId,
+        Blake2_128Concat,
+        T::AccountId,
+        TierId,
+    >;
+
+    /// Stretch goals of each campaign, in increasing order of target.
+    #[pallet::storage]
+    pub type StretchGoals<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        CampaignId,
+        StretchGoalsOf<T>,
+        ValueQuery,
+    >;
+
+    /// Number of stretch goals each campaign has reached.
+    #[pallet::storage]
+    pub type ReachedStretchGoals<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        CampaignId,
+        u32,
+        ValueQuery,
+    >;
+
+    /// Campaigns listed under each category. Cancelled campaigns are delisted.
+    #[pallet::storage]
+    pub type CampaignsByCategory<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        Category,
+        Blake2_128Concat,
+        CampaignId,
+        (),
+        OptionQuery,
+    >;
+
+    /// Campaigns listed under each tag. Cancelled campaigns are delisted.
+    #[pallet::storage]
+    pub type CampaignsByTag<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        TagId,
+        Blake2_128Concat,
+        CampaignId,
+        (),
+        OptionQuery,
+    >;
+
+    /// Campaigns removed from the category and tag indices by cancellation, until they are
+    /// reaped. They stay delisted once settled.
+    #[pallet::storage]
+    pub type DelistedCampaigns<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        CampaignId,
+        (),
+        OptionQuery,
+    >;
+
+    /// Campaigns of each owner, until they are reaped.
+    #[pallet::storage]
+    pub type CampaignsByOwner<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        CampaignId,
+        (),
+        OptionQuery,
+    >;
+
+    /// How far each campaign's end has been pushed out so far.
+    #[pallet::storage]
+    pub type CampaignExtensions<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        CampaignId,
+        MomentOf<T>,
+        ValueQuery,
+    >;
+
+    /// Latest metadata revisions of each campaign, oldest first.
+    #[pallet::storage]
+    pub type MetadataRevisions<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        CampaignId,
+        RevisionsOf<T>,
+        ValueQuery,
+    >;
+
+    /// When each paused campaign was paused.
+    #[pallet::storage]
+    pub type PausedAt<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        CampaignId,
+        MomentOf<T>,
+    >;
+
+    /// Anti-sniping window of campaigns: a contribution this close to the end pushes it out by
+    /// the window.
+    #[pallet::storage]
+    pub type AutoExtensions<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        CampaignId,
+        MomentOf<T>,
+    >;
+
+    /// Open fraud challenge against each campaign.
+    #[pallet::storage]
+    pub type Challenges<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        CampaignId,
+        Challenge<T>,
+    >;
+
+    #[pallet::storage]
+    pub type NextRoundId<T> = StorageValue<_, RoundId, ValueQuery>;
+
+    /// Index of the current `TreasurySpendPeriod` and what was spent from the treasury in it.
+    #[pallet::storage]
+    pub type TreasurySpent<T: Config> = StorageValue<_, (MomentOf<T>, BalanceOf<T>), ValueQuery>;
+
+    #[pallet::storage]
+    pub type Rounds<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        RoundId,
+        Round<T>,
+    >;
+
+    #[pallet::storage]
+    pub type RoundApplications<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        RoundId,
+        Blake2_128Concat,
+        CampaignId,
+        ApplicationStatus<T::Hash>,
+    >;
+
+    /// The round each campaign has a pending or approved application in.
+    #[pallet::storage]
+    pub type CampaignRound<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        CampaignId,
+        RoundId,
+    >;
+
+    /// Approved campaigns taking part in each round's quadratic match.
+    #[pallet::storage]
+    pub type RoundCampaigns<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        RoundId,
+        BoundedVec<CampaignId, T::MaxRoundCampaigns>,
+        ValueQuery,
+    >;
+
+    /// Coordinator and message state of anti-collusion rounds.
+    #[pallet::storage]
+    pub type Coordinations<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        RoundId,
+        Coordination<T>,
+    >;
+
+    /// Signups to each anti-collusion round and their budgets, until the round is finalized.
+    #[pallet::storage]
+    pub type RoundSignups<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        RoundId,
+        BoundedVec<(T::AccountId, BalanceOf<T>), T::MaxSignups>,
+        ValueQuery,
+    >;
+
+    /// Verified coordinator tally of each anti-collusion round.
+    #[pallet::storage]
+    pub type RoundTallies<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        RoundId,
+        BoundedVec<TallyEntry<BalanceOf<T>>, T::MaxRoundCampaigns>,
+    >;
+
+    #[pallet::storage]
+    pub type RoundMatches<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        RoundId,
+        Blake2_128Concat,
+        CampaignId,
+        BalanceOf<T>,
+        ValueQuery,
+    >;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// Campaign created. [campaign_id, owner]
+        CampaignCreated { campaign_id: CampaignId, owner: T::AccountId },
+        /// Campaign metadata updated, the deposit adjusted to its new size. [campaign_id, revision, old_hash, new_hash, reserved, unreserved]
+        MetadataUpdated {
+            campaign_id: CampaignId,
+            revision: u32,
+            old_hash: T::Hash,
+            new_hash: T::Hash,
+            reserved: BalanceOf<T>,
+            unreserved: BalanceOf<T>,
+        },
+        /// Campaign caps updated. [campaign_id, soft_cap, hard_cap]
+        CapsUpdated { campaign_id: CampaignId, soft_cap: BalanceOf<T>, hard_cap: BalanceOf<T> },
+        /// Campaign end pushed out. [campaign_id, end]
+        CampaignExtended { campaign_id: CampaignId, end: MomentOf<T> },
+        /// Anti-sniping window set or removed. [campaign_id, window]
+        AutoExtensionSet { campaign_id: CampaignId, window: Option<MomentOf<T>> },
+        /// Campaign paused by its owner. [campaign_id]
+        CampaignPaused { campaign_id: CampaignId },
+        /// Paused campaign resumed. [campaign_id, end]
+        CampaignResumed { campaign_id: CampaignId, end: MomentOf<T> },
+        /// Campaign cancelled. [campaign_id]
+        CampaignCancelled { campaign_id: CampaignId },
+        /// Campaign cancelled by governance. [campaign_id, reason, slashed]
+        CampaignForceCancelled { campaign_id: CampaignId, reason: T::Hash, slashed: BalanceOf<T> },
+        /// Contribution made to campaign, `amount` being the accepted part. [campaign_id, who, amount]
+        ContributionMade { campaign_id: CampaignId, who: T::AccountId, amount: BalanceOf<T> },
+        /// Campaign finalized. [campaign_id, status]
+        CampaignFinalized { campaign_id: CampaignId, status: CampaignStatus },
+        /// Campaign raised the target of a stretch goal. [campaign_id, index, target]
+        StretchGoalReached { campaign_id: CampaignId, index: u32, target: BalanceOf<T> },
+        /// Contributor selected a reward tier. [campaign_id, who, tier_id]
+        TierSelected { campaign_id: CampaignId, who: T::AccountId, tier_id: TierId },
+        /// Sealed contribution committed. [campaign_id, who, bound]
+        ContributionCommitted { campaign_id: CampaignId, who: T::AccountId, bound: BalanceOf<T> },
+        /// Sealed contribution revealed, the excess over the amount unreserved. [campaign_id, who, amount, refunded]
+        ContributionRevealed {
+            campaign_id: CampaignId,
+            who: T::AccountId,
+            amount: BalanceOf<T>,
+            refunded: BalanceOf<T>,
+        },
+        /// Unrevealed commitment released. [campaign_id, who, amount]
+        CommitmentWithdrawn { campaign_id: CampaignId, who: T::AccountId, amount: BalanceOf<T> },
+        /// Refund claimed. [campaign_id, who, amount]
+        RefundClaimed { campaign_id: CampaignId, who: T::AccountId, amount: BalanceOf<T> },
+        /// Batch of contributions settled. [campaign_id, processed, amount, fee, bounty]
+        ContributionsSettled {
+            campaign_id: CampaignId,
+            processed: u32,
+            amount: BalanceOf<T>,
+            fee: BalanceOf<T>,
+            bounty: BalanceOf<T>,
+        },
+        /// Every contribution of the campaign has been settled. [campaign_id]
+        CampaignSettled { campaign_id: CampaignId },
+        /// Campaign removed from storage and archived. [campaign_id]
+        CampaignReaped { campaign_id: CampaignId },
+        /// Round created. [round_id, start, end]
+        RoundCreated { round_id: RoundId, start: MomentOf<T>, end: MomentOf<T> },
+        /// Matching pool funded. [round_id, who, amount]
+        RoundFunded { round_id: RoundId, who: T::AccountId, amount: BalanceOf<T> },
+        /// Campaign applied to a round. [round_id, campaign_id]
+        ApplicationSubmitted { round_id: RoundId, campaign_id: CampaignId },
+        /// Round application approved. [round_id, campaign_id]
+        ApplicationApproved { round_id: RoundId, campaign_id: CampaignId },
+        /// Round application rejected. [round_id, campaign_id, reason]
+        ApplicationRejected { round_id: RoundId, campaign_id: CampaignId, reason: T::Hash },
+        /// Matching funds paid to a campaign. [round_id, campaign_id, amount]
+        MatchAllocated { round_id: RoundId, campaign_id: CampaignId, amount: BalanceOf<T> },
+        /// Coordinator appointed, making the round an anti-collusion round. [round_id, coordinator]
+        CoordinatorSet { round_id: RoundId, coordinator: T::AccountId },
+        /// Contributor signed up to an anti-collusion round. [round_id, who, index, public_key, budget]
+        SignedUp {
+            round_id: RoundId,
+            who: T::AccountId,
+            index: u32,
+            public_key: PublicKeyOf<T>,
+            budget: BalanceOf<T>,
+        },
+        /// Encrypted message published to an anti-collusion round. [round_id, index, message]
+        MessagePublished { round_id: RoundId, index: u32, message: MessageOf<T> },
+        /// Coordinator tally verified. [round_id]
+        TallySubmitted { round_id: RoundId },
+        /// Tallied budget paid to a campaign. [round_id, campaign_id, amount]
+        TallyAllocated { round_id: RoundId, campaign_id: CampaignId, amount: BalanceOf<T> },
+        /// Share of the unspent budget returned to a signup. [round_id, who, amount]
+        SignupRefunded { round_id: RoundId, who: T::AccountId, amount: BalanceOf<T> },
+        /// Unallocated matching funds returned to the treasury. [round_id, amount]
+        RoundSwept { round_id: RoundId, amount: BalanceOf<T> },
+        /// Round finalized. [round_id]
+        RoundFinalized { round_id: RoundId },
+        /// Registry parameters updated. [parameters]
+        ParametersUpdated { parameters: ParametersOf<T> },
+        /// Round curator appointed or removed. [round_id, curator]
+        RoundCuratorSet { round_id: RoundId, curator: Option<T::AccountId> },
+        /// Campaign challenged as fraudulent. [campaign_id, challenger, evidence]
+        CampaignChallenged { campaign_id: CampaignId, challenger: T::AccountId, evidence: T::Hash },
+        /// Challenge upheld, owner's deposit paid to the challenger. [campaign_id, challenger, reward]
+        ChallengeUpheld { campaign_id: CampaignId, challenger: T::AccountId, reward: BalanceOf<T> },
+        /// Challenge dismissed as frivolous, bond slashed. [campaign_id, challenger, slashed]
+        ChallengeDismissed { campaign_id: CampaignId, challenger: T::AccountId, slashed: BalanceOf<T> },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// Campaign not found
+        CampaignNotFound,
+        /// Not the campaign owner
+        NotOwner,
+        /// Invalid time range specified
+        InvalidTimeRange,
+        /// Invalid cap values
+        CapsInvalid,
+        /// Campaign is not active
+        NotActive,
+        /// Campaign hard cap would be exceeded
+        HardCapExceeded,
+        /// Campaign already finalized
+        AlreadyFinalized,
+        /// No contribution found to refund
+        NoContributionFound,
+        /// Maximum number of active campaigns reached
+        TooManyActiveCampaigns,
+        /// Campaign has not failed or been cancelled
+        NotRefundable,
+        /// Round not found
+        RoundNotFound,
+        /// Round has already been finalized
+        RoundAlreadyFinalized,
+        /// Round has not ended yet
+        RoundNotEnded,
+        /// Applications for the round are closed
+        ApplicationsClosed,
+        /// Campaign already has an application in a round, or was rejected by this round
+        AlreadyApplied,
+        /// No application found for the campaign in this round
+        ApplicationNotFound,
+        /// Application has already been decided
+        ApplicationNotPending,
+        /// Campaign ends after the round
+        CampaignOutsideRound,
+        /// Maximum number of approved campaigns in the round reached
+        TooManyRoundCampaigns,
+        /// Contribution is below the minimum contribution
+        ContributionTooLow,
+        /// Not the curator of the round
+        NotCurator,
+        /// Campaign already has an open challenge
+        AlreadyChallenged,
+        /// Campaign has no open challenge
+        NoChallenge,
+        /// Campaign has not finished yet
+        NotFinished,
+        /// Contributions are being settled in batches
+        SettlementInProgress,
+        /// Settlement cursor does not fit in storage
+        CursorTooLong,
+        /// Campaign has not been settled
+        NotSettled,
+        /// Retention period after settlement has not passed
+        RetentionPeriodNotOver,
+        /// Campaign's round has not been finalized yet
+        RoundNotFinalized,
+        /// Amount exceeds what the origin may still spend in the current period
+        InsufficientPermission,
+        /// Campaign only accepts sealed contributions
+        PrivateCampaign,
+        /// Campaign is not part of a private round
+        NotPrivate,
+        /// Contributor already has a commitment for the campaign
+        AlreadyCommitted,
+        /// No commitment found for the contributor
+        NoCommitment,
+        /// Amount and salt do not match the commitment
+        InvalidReveal,
+        /// Campaign is not in its reveal window
+        RevealClosed,
+        /// Commitment can still be revealed
+        RevealOpen,
+        /// Round has no coordinator
+        NotCoordinated,
+        /// Not the coordinator of the round
+        NotCoordinator,
+        /// Coordinator can no longer be changed
+        CoordinatorLocked,
+        /// The runtime cannot verify tallies, so rounds cannot be coordinated
+        TallyVerificationDisabled,
+        /// Round is not accepting signups or messages
+        OutsideVotingPeriod,
+        /// Round already has a tally
+        TallyAlreadySubmitted,
+        /// Round has no tally yet and its tally timeout has not passed
+        TallyMissing,
+        /// Tally does not match the round or its proof
+        InvalidTally,
+        /// Reward tier requires more than the hard cap or has a zero supply
+        InvalidTier,
+        /// Campaign has no such reward tier
+        TierNotFound,
+        /// Contribution is below the minimum of the reward tier
+        BelowTierMinimum,
+        /// Every reward of the tier has been claimed
+        TierSoldOut,
+        /// Stretch goal targets must increase from a first target between the soft and hard caps
+        InvalidStretchGoals,
+        /// Campaign has no further stretch goal beyond its hard cap
+        NoStretchGoal,
+        /// Stretch goal preceding the next one has not been reached
+        StretchGoalNotReached,
+        /// Extension exceeds `MaxExtension` or the end of the campaign's round
+        ExtensionTooLong,
+        /// Campaign is paused
+        CampaignPaused,
+        /// Campaign is not paused
+        NotPaused,
+        /// Metadata lists a tag more than once
+        DuplicateTag,
+        /// Content CID is not a valid CIDv0 or CIDv1 string
+        InvalidCid,
+        /// Content MIME type is not of the form `type/subtype`
+        InvalidMimeType,
+        /// Only the description can be edited once the campaign has started
+        RestrictedEdit,
+        /// Campaign name is empty or only whitespace
+        EmptyName,
+        /// Campaign name is not valid UTF-8
+        NameNotUtf8,
+        /// Campaign name contains control characters
+        NameHasControlCharacters,
+        /// Campaign link is not an `https://` or `ipfs://` URI
+        UnsupportedLinkScheme,
+        /// Campaign link is not a well-formed URI
+        MalformedLink,
+        /// A campaign of the round is not finalized or can still reveal contributions
+        RoundCampaignsPending,
+        /// Campaign has reached its end
+        CampaignEnded,
+        /// A campaign of the round has an open challenge
+        RoundCampaignsChallenged,
+        /// Settlement batches must process at least one contribution
+        ZeroSettlementLimit,
+        /// Successful campaign is still within its payout delay
+        PayoutDelayNotOver,
+        /// Campaign is waiting for approval to a private round
+        PrivateApplicationPending,
+        /// Campaign already has contributions or commitments, so it cannot join a private round
+        CampaignHasContributions,
+        /// Threshold funding model with a zero share
+        ZeroThreshold,
+        /// Maximum number of signups to the round reached
+        TooManySignups,
+    }
+
+    #[pallet::genesis_config]
+    #[derive(frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        pub min_contribution: BalanceOf<T>,
+        pub match_cap: Perbill,
+        pub challenge_bond: BalanceOf<T>,
+        pub success_fee: Permill,
+        pub settlement_bounty: BalanceOf<T>,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            Parameters::<T>::put(RegistryParameters {
+                min_contribution: self.min_contribution,
+                match_cap: self.match_cap,
+                challenge_bond: self.challenge_bond,
+                success_fee: self.success_fee,
+                settlement_bounty: self.settlement_bounty,
+            });
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+            let mut weight = T::DbWeight::get().reads(1);
+            // Campaigns may still be in the old layout while a migration is ongoing
+            if Self::on_chain_storage_version() < STORAGE_VERSION {
+                return weight;
+            }
+            let now = T::Timestamp::now();
+            
+            let active = ActiveCampaigns::<T>::get();
+            let mut updated = active.clone();
+            
+            for campaign_id in active.iter() {
+                weight = weight.saturating_add(T::DbWeight::get().reads(1));
+                
+                if let Some(mut campaign) = Campaigns::<T>::get(campaign_id) {
+                    // Owners cannot hold contributions and finalization back indefinitely
+                    if campaign.status == CampaignStatus::Paused {
+                        weight = weight.saturating_add(T::DbWeight::get().reads(1));
+                        let paused_at = PausedAt::<T>::get(campaign_id).unwrap_or(now);
+                        if now >= paused_at.saturating_add(T::MaxPauseDuration::get()) {
+                            // The owner did not get to choose, so the paused time is made up for
+                            Self::do_resume(*campaign_id, &mut campaign, true);
+                            Campaigns::<T>::insert(campaign_id, campaign.clone());
+                            
+                            weight = weight.saturating_add(T::DbWeight::get().reads_writes(3, 3));
+                        }
+                    }
+                    
+                    // Campaigns of private rounds wait for their reveal window to close
+                    if campaign.status == CampaignStatus::Active &&
+                        now >= Self::reveal_deadline(*campaign_id, &campaign)
+                    {
+                        // Finalize campaign
+                        campaign.status = if Self::funding_succeeded(&campaign) {
+                            CampaignStatus::Success
+                        } else {
+                            CampaignStatus::Failed
+                        };
+                        
+                        Campaigns::<T>::insert(campaign_id, campaign.clone());
+                        updated.retain(|id| id != campaign_id);
+                        
+                        Self::deposit_event(Event::CampaignFinalized {
+                            campaign_id: *campaign_id,
+                            status: campaign.status,
+                        });
+                        
+                        weight = weight.saturating_add(T::DbWeight::get().writes(1));
+                    }
+                }
+            }
+            
+            if updated != active {
+                ActiveCampaigns::<T>::put(updated);
+                weight = weight.saturating_add(T::DbWeight::get().writes(1));
+            }
+            
+            weight
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn try_state(_n: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+            Self::do_try_state()
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        #[pallet::call_index(0)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(
+            2,
+            7 + T::MaxTags::get() as u64,
+        ))]
+        pub fn create_campaign(
+            origin: OriginFor<T>,
+            metadata: Metadata<T>,
+            start: MomentOf<T>,
+            end: MomentOf<T>,
+            soft_cap: BalanceOf<T>,
+            hard_cap: BalanceOf<T>,
+            tiers: TiersOf<T>,
+            stretch_goals: StretchGoalsOf<T>,
+            funding_model: FundingModel,
+        ) -> DispatchResult {
+            let owner = ensure_signed(origin)?;
+            
+            ensure!(start < end, Error::<T>::InvalidTimeRange);
+            ensure!(soft_cap <= hard_cap, Error::<T>::CapsInvalid);
+            ensure!(
+                !soft_cap.is_zero() && !hard_cap.is_zero(),
+                Error::<T>::CapsInvalid
+            );
+            ensure!(
+                tiers.iter().all(|tier| tier.min_amount <= hard_cap && tier.supply != Some(0)),
+                Error::<T>::InvalidTier
+            );
+            // A zero share would count a campaign that raised nothing as successful
+            ensure!(
+                funding_model != FundingModel::Threshold(Perbill::zero()),
+                Error::<T>::ZeroThreshold
+            );
+            Self::ensure_stretch_goals(&stretch_goals, soft_cap, hard_cap)?;
+            Self::ensure_metadata(&metadata)?;
+            
+            let now = T::Timestamp::now();
+            let status = if now < start {
+                CampaignStatus::Upcoming
+            } else if now <= end {
+                CampaignStatus::Active
+            } else {
+                return Err(Error::<T>::InvalidTimeRange.into());
+            };
+            
+            // Reserve the deposit
+            let deposit = Self::metadata_deposit(&metadata);
+            T::Currency::reserve(&owner, deposit)?;
+            
+            let campaign_id = NextCampaignId::<T>::get();
+            let campaign = Campaign {
+                owner: owner.clone(),
+                metadata,
+                start,
+                end,
+                soft_cap,
+                hard_cap,
+                matched: Zero::zero(),
+                status: status.clone(),
+                deposit,
+                contributors: 0,
+                funding_model,
+            };
+            
+            Self::list(campaign_id, &campaign.metadata);
+            Self::log_revision(campaign_id, T::Hashing::hash_of(&campaign.metadata));
+            CampaignsByOwner::<T>::insert(&owner, campaign_id, ());
+            Campaigns::<T>::insert(campaign_id, campaign);
+            CampaignTiers::<T>::insert(campaign_id, tiers);
+            StretchGoals::<T>::insert(campaign_id, stretch_goals);
+            NextCampaignId::<T>::put(campaign_id.saturating_add(1));
+            
+            if status == CampaignStatus::Active {
+                ActiveCampaigns::<T>::try_mutate(|campaigns| {
+                    campaigns.try_push(campaign_id)
                 }).map_err(|_| Error::<T>::TooManyActiveCampaigns)?;
             }
             
-            Self::deposit_event(Event::CampaignCreated {
+            Self::deposit_event(Event::CampaignCreated {
+                campaign_id,
+                owner,
+            });
+            
+            Ok(())
+        }
+
+        #[pallet::call_index(1)]
+        #[pallet::weight(Weight::from_parts(5_000, 0) + T::DbWeight::get().reads_writes(
+            3,
+            5 + 2 * T::MaxTags::get() as u64,
+        ))]
+        pub fn update_metadata(
+            origin: OriginFor<T>,
+            campaign_id: CampaignId,
+            metadata: Metadata<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            
+            Campaigns::<T>::try_mutate(campaign_id, |maybe_campaign| -> DispatchResult {
+                let campaign = maybe_campaign.as_mut().ok_or(Error::<T>::CampaignNotFound)?;
+                ensure!(campaign.owner == who, Error::<T>::NotOwner);
+                match campaign.status {
+                    CampaignStatus::Upcoming => {},
+                    // Contributors backed the rest of the metadata as it is
+                    CampaignStatus::Active | CampaignStatus::Paused => ensure!(
+                        Metadata { description: campaign.metadata.description.clone(), ..metadata.clone() } ==
+                            campaign.metadata,
+                        Error::<T>::RestrictedEdit
+                    ),
+                    _ => return Err(Error::<T>::NotActive.into()),
+                }
+                Self::ensure_metadata(&metadata)?;
+                
+                // The deposit follows the size of the metadata, adjusted from what is reserved
+                let new_deposit = Self::metadata_deposit(&metadata);
+                let reserved = new_deposit.saturating_sub(campaign.deposit);
+                let unreserved = campaign.deposit.saturating_sub(new_deposit);
+                T::Currency::reserve(&who, reserved)?;
+                T::Currency::unreserve(&who, unreserved);
+                campaign.deposit = new_deposit;
+                
+                let old_hash = T::Hashing::hash_of(&campaign.metadata);
+                let new_hash = T::Hashing::hash_of(&metadata);
+                let revision = Self::log_revision(campaign_id, new_hash);
+                
+                Self::delist(campaign_id, &campaign.metadata);
+                Self::list(campaign_id, &metadata);
+                campaign.metadata = metadata;
+                Self::deposit_event(Event::MetadataUpdated {
+                    campaign_id,
+                    revision,
+                    old_hash,
+                    new_hash,
+                    reserved,
+                    unreserved,
+                });
+                Ok(())
+            })
+        }
+
+        #[pallet::call_index(2)]
+        #[pallet::weight(Weight::from_parts(5_000, 0) + T::DbWeight::get().reads_writes(3, 1))]
+        pub fn unlock_stretch_goal(
+            origin: OriginFor<T>,
+            campaign_id: CampaignId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            
+            Campaigns::<T>::try_mutate(campaign_id, |maybe_campaign| -> DispatchResult {
+                let campaign = maybe_campaign.as_mut().ok_or(Error::<T>::CampaignNotFound)?;
+                ensure!(campaign.owner == who, Error::<T>::NotOwner);
+                ensure!(campaign.status == CampaignStatus::Active, Error::<T>::NotActive);
+                
+                let goals = StretchGoals::<T>::get(campaign_id);
+                let next = goals
+                    .iter()
+                    .position(|goal| goal.target > campaign.hard_cap)
+                    .ok_or(Error::<T>::NoStretchGoal)?;
+                // Goals are increasing and the first is within the cap, so the next has a predecessor
+                ensure!(
+                    ReachedStretchGoals::<T>::get(campaign_id) as usize >= next,
+                    Error::<T>::StretchGoalNotReached
+                );
+                
+                campaign.hard_cap = goals[next].target;
+                
+                Self::deposit_event(Event::CapsUpdated {
+                    campaign_id,
+                    soft_cap: campaign.soft_cap,
+                    hard_cap: campaign.hard_cap,
+                });
+                Ok(())
+            })
+        }
+
+        #[pallet::call_index(3)]
+        #[pallet::weight(Weight::from_parts(5_000, 0) + T::DbWeight::get().reads_writes(3, 1))]
+        pub fn set_caps(
+            origin: OriginFor<T>,
+            campaign_id: CampaignId,
+            soft_cap: BalanceOf<T>,
+            hard_cap: BalanceOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            
+            ensure!(soft_cap <= hard_cap, Error::<T>::CapsInvalid);
+            ensure!(
+                !soft_cap.is_zero() && !hard_cap.is_zero(),
+                Error::<T>::CapsInvalid
+            );
+            
+            Campaigns::<T>::try_mutate(campaign_id, |maybe_campaign| -> DispatchResult {
+                let campaign = maybe_campaign.as_mut().ok_or(Error::<T>::CampaignNotFound)?;
+                ensure!(campaign.owner == who, Error::<T>::NotOwner);
+                ensure!(campaign.status == CampaignStatus::Upcoming, Error::<T>::NotActive);
+                ensure!(
+                    CampaignTiers::<T>::get(campaign_id)
+                        .iter()
+                        .all(|tier| tier.min_amount <= hard_cap),
+                    Error::<T>::InvalidTier
+                );
+                Self::ensure_stretch_goals(&StretchGoals::<T>::get(campaign_id), soft_cap, hard_cap)?;
+                
+                campaign.soft_cap = soft_cap;
+                campaign.hard_cap = hard_cap;
+                
+                Self::deposit_event(Event::CapsUpdated {
+                    campaign_id,
+                    soft_cap,
+                    hard_cap,
+                });
+                Ok(())
+            })
+        }
+
+        #[pallet::call_index(4)]
+        #[pallet::weight(Weight::from_parts(5_000, 0) + T::DbWeight::get().reads_writes(4, 2))]
+        pub fn extend_campaign(
+            origin: OriginFor<T>,
+            campaign_id: CampaignId,
+            new_end: MomentOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            
+            Campaigns::<T>::try_mutate(campaign_id, |maybe_campaign| -> DispatchResult {
+                let campaign = maybe_campaign.as_mut().ok_or(Error::<T>::CampaignNotFound)?;
+                ensure!(campaign.owner == who, Error::<T>::NotOwner);
+                ensure!(campaign.status == CampaignStatus::Active, Error::<T>::NotActive);
+                // An ended campaign may be in its reveal window, which must not turn back into
+                // the commit phase
+                ensure!(T::Timestamp::now() < campaign.end, Error::<T>::NotActive);
+                ensure!(new_end > campaign.end, Error::<T>::InvalidTimeRange);
+                ensure!(
+                    new_end <= Self::latest_end(campaign_id, campaign),
+                    Error::<T>::ExtensionTooLong
+                );
+                
+                Self::do_extend(campaign_id, campaign, new_end);
+                Ok(())
+            })
+        }
+
+        #[pallet::call_index(5)]
+        #[pallet::weight(Weight::from_parts(5_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_auto_extension(
+            origin: OriginFor<T>,
+            campaign_id: CampaignId,
+            window: Option<MomentOf<T>>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            
+            let campaign = Campaigns::<T>::get(campaign_id).ok_or(Error::<T>::CampaignNotFound)?;
+            ensure!(campaign.owner == who, Error::<T>::NotOwner);
+            ensure!(
+                matches!(campaign.status, CampaignStatus::Upcoming | CampaignStatus::Active),
+                Error::<T>::NotActive
+            );
+            
+            AutoExtensions::<T>::set(campaign_id, window.filter(|window| !window.is_zero()));
+            
+            Self::deposit_event(Event::AutoExtensionSet { campaign_id, window });
+            Ok(())
+        }
+
+        #[pallet::call_index(6)]
+        #[pallet::weight(Weight::from_parts(5_000, 0) + T::DbWeight::get().reads_writes(1, 2))]
+        pub fn pause_campaign(
+            origin: OriginFor<T>,
+            campaign_id: CampaignId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            
+            Campaigns::<T>::try_mutate(campaign_id, |maybe_campaign| -> DispatchResult {
+                let campaign = maybe_campaign.as_mut().ok_or(Error::<T>::CampaignNotFound)?;
+                ensure!(campaign.owner == who, Error::<T>::NotOwner);
+                ensure!(campaign.status != CampaignStatus::Paused, Error::<T>::CampaignPaused);
+                let now = T::Timestamp::now();
+                ensure!(
+                    campaign.status == CampaignStatus::Active && now < campaign.end,
+                    Error::<T>::NotActive
+                );
+                
+                // Paused campaigns keep their place in `ActiveCampaigns` so resuming cannot fail
+                campaign.status = CampaignStatus::Paused;
+                PausedAt::<T>::insert(campaign_id, now);
+                
+                Self::deposit_event(Event::CampaignPaused { campaign_id });
+                Ok(())
+            })
+        }
+
+        #[pallet::call_index(7)]
+        #[pallet::weight(Weight::from_parts(5_000, 0) + T::DbWeight::get().reads_writes(4, 2))]
+        pub fn resume_campaign(
+            origin: OriginFor<T>,
+            campaign_id: CampaignId,
+            extend_end: bool,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            
+            Campaigns::<T>::try_mutate(campaign_id, |maybe_campaign| -> DispatchResult {
+                let campaign = maybe_campaign.as_mut().ok_or(Error::<T>::CampaignNotFound)?;
+                ensure!(campaign.owner == who, Error::<T>::NotOwner);
+                ensure!(campaign.status == CampaignStatus::Paused, Error::<T>::NotPaused);
+                
+                Self::do_resume(campaign_id, campaign, extend_end);
+                Ok(())
+            })
+        }
+
+        #[pallet::call_index(8)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 2))]
+        pub fn cancel_campaign(
+            origin: OriginFor<T>,
+            campaign_id: CampaignId,
+        ) -> DispatchResult {
+            // `None` when cancelled by the admin origin
+            let maybe_who = match T::AdminOrigin::try_origin(origin) {
+                Ok(_) => None,
+                Err(origin) => Some(ensure_signed(origin)?),
+            };
+            
+            Campaigns::<T>::try_mutate(campaign_id, |maybe_campaign| -> DispatchResult {
+                let campaign = maybe_campaign.as_mut().ok_or(Error::<T>::CampaignNotFound)?;
+                if let Some(who) = maybe_who {
+                    ensure!(campaign.owner == who, Error::<T>::NotOwner);
+                    // The deposit backs the challenge until governance resolves it
+                    ensure!(!Challenges::<T>::contains_key(campaign_id), Error::<T>::AlreadyChallenged);
+                }
+                ensure!(
+                    matches!(
+                        campaign.status,
+                        CampaignStatus::Upcoming | CampaignStatus::Active | CampaignStatus::Paused
+                    ),
+                    Error::<T>::AlreadyFinalized
+                );
+                
+                Self::do_cancel(campaign_id, campaign);
+                
+                // Unreserve the deposit for the owner
+                T::Currency::unreserve(&campaign.owner, campaign.deposit);
+                campaign.deposit = Zero::zero();
+                
+                Self::deposit_event(Event::CampaignCancelled { campaign_id });
+                Ok(())
+            })
+        }
+
+        #[pallet::call_index(9)]
+        #[pallet::weight(Weight::from_parts(15_000, 0) + T::DbWeight::get().reads_writes(2, 3))]
+        pub fn force_cancel_campaign(
+            origin: OriginFor<T>,
+            campaign_id: CampaignId,
+            reason: T::Hash,
+            slash_deposit: bool,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            
+            Campaigns::<T>::try_mutate(campaign_id, |maybe_campaign| -> DispatchResult {
+                let campaign = maybe_campaign.as_mut().ok_or(Error::<T>::CampaignNotFound)?;
+                ensure!(
+                    campaign.status != CampaignStatus::Cancelled && campaign.status != CampaignStatus::Settled,
+                    Error::<T>::AlreadyFinalized
+                );
+                // Part of the contributions have already been paid out or refunded
+                ensure!(
+                    !SettlementCursors::<T>::contains_key(campaign_id),
+                    Error::<T>::SettlementInProgress
+                );
+                
+                Self::do_cancel(campaign_id, campaign);
+                
+                let slashed = if slash_deposit {
+                    let (imbalance, _) = T::Currency::slash_reserved(&campaign.owner, campaign.deposit);
+                    let slashed = imbalance.peek();
+                    T::Slashed::on_unbalanced(imbalance);
+                    slashed
+                } else {
+                    T::Currency::unreserve(&campaign.owner, campaign.deposit);
+                    Zero::zero()
+                };
+                campaign.deposit = Zero::zero();
+                
+                Self::deposit_event(Event::CampaignForceCancelled { campaign_id, reason, slashed });
+                Ok(())
+            })
+        }
+
+        #[pallet::call_index(10)]
+        #[pallet::weight(
+            Weight::from_parts(15_000, 0) + T::DbWeight::get().reads_writes(13, 7).saturating_add(T::OnContribution::weight())
+        )]
+        pub fn contribute(
+            origin: OriginFor<T>,
+            campaign_id: CampaignId,
+            amount: BalanceOf<T>,
+            tier: Option<TierId>,
+            allow_partial: bool,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            
+            Campaigns::<T>::try_mutate(campaign_id, |maybe_campaign| -> DispatchResult {
+                let campaign = maybe_campaign.as_mut().ok_or(Error::<T>::CampaignNotFound)?;
+                ensure!(campaign.status != CampaignStatus::Paused, Error::<T>::CampaignPaused);
+                ensure!(campaign.status == CampaignStatus::Active, Error::<T>::NotActive);
+                // Campaigns stay active past their end until the next block finalizes them
+                ensure!(T::Timestamp::now() < campaign.end, Error::<T>::CampaignEnded);
+                ensure!(Self::reveal_window(campaign_id).is_none(), Error::<T>::PrivateCampaign);
+                // Public contributions would follow the campaign into a private round
+                ensure!(
+                    Self::applied_reveal_window(campaign_id).is_none(),
+                    Error::<T>::PrivateApplicationPending
+                );
+                
+                // A partial contribution takes whatever headroom is left under the hard cap
+                let headroom = campaign.hard_cap.saturating_sub(campaign.matched);
+                let amount = if allow_partial {
+                    ensure!(!headroom.is_zero(), Error::<T>::HardCapExceeded);
+                    amount.min(headroom)
+                } else {
+                    amount
+                };
+                ensure!(amount <= headroom, Error::<T>::HardCapExceeded);
+                // Checked after clamping, so the last sliver of headroom cannot be filled with dust
+                ensure!(
+                    amount >= Parameters::<T>::get().min_contribution,
+                    Error::<T>::ContributionTooLow
+                );
+                let new_total = campaign.matched.saturating_add(amount);
+                
+                // Reserve the contribution
+                T::Currency::reserve(&who, amount)?;
+                
+                // Update contribution tracking
+                CampaignContributions::<T>::try_mutate(
+                    campaign_id,
+                    who.clone(),
+                    |contribution| -> DispatchResult {
+                        if contribution.is_zero() {
+                            campaign.contributors = campaign.contributors.saturating_add(1);
+                        }
+                        let total = contribution.saturating_add(amount);
+                        Self::note_root_sum(campaign_id, *contribution, total);
+                        *contribution = total;
+                        Ok(())
+                    }
+                )?;
+                
+                // Without a tier the contributor keeps any tier selected earlier
+                if let Some(tier_id) = tier {
+                    let total = CampaignContributions::<T>::get(campaign_id, &who);
+                    Self::select_tier(campaign_id, &who, tier_id, total)?;
+                }
+                
+                campaign.matched = new_total;
+                Self::note_stretch_goals(campaign_id, new_total);
+                
+                // Anti-sniping: a late contribution gives everyone else time to react
+                if let Some(window) = AutoExtensions::<T>::get(campaign_id) {
+                    let new_end = T::Timestamp::now()
+                        .saturating_add(window)
+                        .min(Self::latest_end(campaign_id, campaign));
+                    if new_end > campaign.end {
+                        Self::do_extend(campaign_id, campaign, new_end);
+                    }
+                }
+                
+                Self::deposit_event(Event::ContributionMade {
+                    campaign_id,
+                    who: who.clone(),
+                    amount,
+                });
+                Ok(())
+            })?;
+            
+            Self::notify_contribution(campaign_id, &who);
+            Ok(())
+        }
+
+        #[pallet::call_index(11)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(4, 5))]
+        pub fn claim_refund(
+            origin: OriginFor<T>,
+            campaign_id: CampaignId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            
+            let campaign = Campaigns::<T>::get(campaign_id).ok_or(Error::<T>::CampaignNotFound)?;
+            ensure!(
+                campaign.status == CampaignStatus::Failed || campaign.status == CampaignStatus::Cancelled,
+                Error::<T>::NotRefundable
+            );
+            // Once batch settlement has started it refunds the remaining contributions itself
+            ensure!(
+                !SettlementCursors::<T>::contains_key(campaign_id),
+                Error::<T>::SettlementInProgress
+            );
+            
+            let contribution = CampaignContributions::<T>::take(campaign_id, who.clone());
+            ensure!(!contribution.is_zero(), Error::<T>::NoContributionFound);
+            CampaignRefunds::<T>::mutate(campaign_id, |refunded| *refunded = refunded.saturating_add(contribution));
+            if let Some(tier_id) = TierSelections::<T>::take(campaign_id, &who) {
+                TierBackers::<T>::mutate(campaign_id, tier_id, |backers| {
+                    *backers = backers.saturating_sub(1)
+                });
+            }
+            
+            // Unreserve and transfer the contribution back
+            T::Currency::unreserve(&who, contribution);
+            
+            Self::deposit_event(Event::RefundClaimed {
+                campaign_id,
+                who,
+                amount: contribution,
+            });
+            
+            Ok(())
+        }
+
+        #[pallet::call_index(12)]
+        #[pallet::weight(Weight::from_parts(15_000, 0) + T::DbWeight::get().reads_writes(6, 1))]
+        pub fn commit_contribution(
+            origin: OriginFor<T>,
+            campaign_id: CampaignId,
+            commitment: T::Hash,
+            bound: BalanceOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            
+            let campaign = Campaigns::<T>::get(campaign_id).ok_or(Error::<T>::CampaignNotFound)?;
+            ensure!(campaign.status != CampaignStatus::Paused, Error::<T>::CampaignPaused);
+            ensure!(campaign.status == CampaignStatus::Active, Error::<T>::NotActive);
+            ensure!(T::Timestamp::now() < campaign.end, Error::<T>::NotActive);
+            ensure!(Self::reveal_window(campaign_id).is_some(), Error::<T>::NotPrivate);
+            ensure!(
+                bound >= Parameters::<T>::get().min_contribution,
+                Error::<T>::ContributionTooLow
+            );
+            ensure!(
+                !Commitments::<T>::contains_key(campaign_id, &who),
+                Error::<T>::AlreadyCommitted
+            );
+            
+            T::Currency::reserve(&who, bound)?;
+            Commitments::<T>::insert(campaign_id, &who, Commitment { hash: commitment, bound });
+            
+            Self::deposit_event(Event::ContributionCommitted { campaign_id, who, bound });
+            Ok(())
+        }
+
+        #[pallet::call_index(13)]
+        #[pallet::weight(
+            Weight::from_parts(15_000, 0) + T::DbWeight::get().reads_writes(9, 4).saturating_add(T::OnContribution::weight())
+        )]
+        pub fn reveal_contribution(
+            origin: OriginFor<T>,
+            campaign_id: CampaignId,
+            amount: BalanceOf<T>,
+            salt: [u8; 32],
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            
+            Campaigns::<T>::try_mutate(campaign_id, |maybe_campaign| -> DispatchResult {
+                let campaign = maybe_campaign.as_mut().ok_or(Error::<T>::CampaignNotFound)?;
+                let now = T::Timestamp::now();
+                ensure!(
+                    campaign.status == CampaignStatus::Active &&
+                        now >= campaign.end &&
+                        now < Self::reveal_deadline(campaign_id, campaign),
+                    Error::<T>::RevealClosed
+                );
+                
+                let commitment = Commitments::<T>::take(campaign_id, &who).ok_or(Error::<T>::NoCommitment)?;
+                ensure!(
+                    T::Hashing::hash_of(&(amount, salt)) == commitment.hash && amount <= commitment.bound,
+                    Error::<T>::InvalidReveal
+                );
+                
+                // Whatever no longer fits under the hard cap is refunded with the excess, and so is
+                // an amount left below the minimum contribution
+                let amount = amount.min(campaign.hard_cap.saturating_sub(campaign.matched));
+                let amount = if amount < Parameters::<T>::get().min_contribution {
+                    Zero::zero()
+                } else {
+                    amount
+                };
+                let refunded = commitment.bound.saturating_sub(amount);
+                T::Currency::unreserve(&who, refunded);
+                
+                if !amount.is_zero() {
+                    CampaignContributions::<T>::mutate(campaign_id, &who, |contribution| {
+                        if contribution.is_zero() {
+                            campaign.contributors = campaign.contributors.saturating_add(1);
+                        }
+                        let total = contribution.saturating_add(amount);
+                        Self::note_root_sum(campaign_id, *contribution, total);
+                        *contribution = total;
+                    });
+                    campaign.matched = campaign.matched.saturating_add(amount);
+                    Self::note_stretch_goals(campaign_id, campaign.matched);
+                }
+                
+                Self::deposit_event(Event::ContributionRevealed {
+                    campaign_id,
+                    who: who.clone(),
+                    amount,
+                    refunded,
+                });
+                Ok(())
+            })?;
+            
+            if CampaignContributions::<T>::contains_key(campaign_id, &who) {
+                Self::notify_contribution(campaign_id, &who);
+            }
+            Ok(())
+        }
+
+        #[pallet::call_index(14)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 2))]
+        pub fn withdraw_commitment(
+            origin: OriginFor<T>,
+            campaign_id: CampaignId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            
+            let campaign = Campaigns::<T>::get(campaign_id).ok_or(Error::<T>::CampaignNotFound)?;
+            ensure!(
+                !matches!(
+                    campaign.status,
+                    CampaignStatus::Upcoming | CampaignStatus::Active | CampaignStatus::Paused
+                ),
+                Error::<T>::RevealOpen
+            );
+            
+            let commitment = Commitments::<T>::take(campaign_id, &who).ok_or(Error::<T>::NoCommitment)?;
+            T::Currency::unreserve(&who, commitment.bound);
+            
+            Self::deposit_event(Event::CommitmentWithdrawn { campaign_id, who, amount: commitment.bound });
+            Ok(())
+        }
+
+        #[pallet::call_index(15)]
+        #[pallet::weight(Weight::from_parts(20_000, 0) + T::DbWeight::get().reads_writes(
+            6 + *limit as u64,
+            3 + 2 * *limit as u64,
+        ))]
+        pub fn settle_batch(
+            origin: OriginFor<T>,
+            campaign_id: CampaignId,
+            limit: u32,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            // An empty batch would store a cursor and lock the campaign out of refunds
+            ensure!(limit > 0, Error::<T>::ZeroSettlementLimit);
+            
+            let mut campaign = Campaigns::<T>::get(campaign_id).ok_or(Error::<T>::CampaignNotFound)?;
+            let success = match campaign.status {
+                CampaignStatus::Success => true,
+                CampaignStatus::Failed | CampaignStatus::Cancelled => false,
+                CampaignStatus::Settled => return Err(Error::<T>::AlreadyFinalized.into()),
+                _ => return Err(Error::<T>::NotFinished.into()),
+            };
+            // An upheld challenge turns payouts into refunds, so it must be resolved first
+            ensure!(!Challenges::<T>::contains_key(campaign_id), Error::<T>::AlreadyChallenged);
+            if success {
+                ensure!(
+                    T::Timestamp::now() >=
+                        Self::reveal_deadline(campaign_id, &campaign).saturating_add(T::PayoutDelay::get()),
+                    Error::<T>::PayoutDelayNotOver
+                );
+            }
+            
+            let parameters = Parameters::<T>::get();
+            let mut contributions = match SettlementCursors::<T>::get(campaign_id) {
+                Some(cursor) => CampaignContributions::<T>::iter_prefix_from(campaign_id, cursor.into_inner()),
+                None => CampaignContributions::<T>::iter_prefix(campaign_id),
+            };
+            
+            let mut processed = 0u32;
+            let mut exhausted = false;
+            let mut amount = BalanceOf::<T>::zero();
+            let mut fees = NegativeImbalanceOf::<T>::zero();
+            while processed < limit {
+                let Some((contributor, contribution)) = contributions.next() else {
+                    exhausted = true;
+                    break;
+                };
+                if success {
+                    let (fee, _) = T::Currency::slash_reserved(&contributor, parameters.success_fee * contribution);
+                    let payout = contribution.saturating_sub(fee.peek());
+                    let remaining = T::Currency::repatriate_reserved(
+                        &contributor,
+                        &campaign.owner,
+                        payout,
+                        BalanceStatus::Free,
+                    )?;
+                    amount = amount.saturating_add(payout.saturating_sub(remaining));
+                    fees.subsume(fee);
+                } else {
+                    let remaining = T::Currency::unreserve(&contributor, contribution);
+                    amount = amount.saturating_add(contribution.saturating_sub(remaining));
+                }
+                processed = processed.saturating_add(1);
+            }
+            
+            let fee = fees.peek();
+            T::OnSuccessFee::on_unbalanced(fees);
+            
+            // Reward the caller from whatever is left of the owner's deposit
+            let bounty = parameters
+                .settlement_bounty
+                .saturating_mul(processed.into())
+                .min(campaign.deposit);
+            let unpaid = T::Currency::repatriate_reserved(&campaign.owner, &caller, bounty, BalanceStatus::Free)?;
+            let bounty = bounty.saturating_sub(unpaid);
+            campaign.deposit = campaign.deposit.saturating_sub(bounty);
+            
+            Self::deposit_event(Event::ContributionsSettled {
                 campaign_id,
-                owner,
+                processed,
+                amount,
+                fee,
+                bounty,
             });
             
+            if exhausted {
+                // The rest of the deposit is kept until the campaign is reaped
+                campaign.status = CampaignStatus::Settled;
+                SettlementCursors::<T>::remove(campaign_id);
+                SettledAt::<T>::insert(campaign_id, T::Timestamp::now());
+                
+                Self::deposit_event(Event::CampaignSettled { campaign_id });
+            } else {
+                let cursor: CursorOf = contributions
+                    .last_raw_key()
+                    .to_vec()
+                    .try_into()
+                    .map_err(|_| Error::<T>::CursorTooLong)?;
+                SettlementCursors::<T>::insert(campaign_id, cursor);
+            }
+            
+            Campaigns::<T>::insert(campaign_id, campaign);
             Ok(())
         }
 
-        #[pallet::weight(5_000 + T::DbWeight::get().reads_writes(1, 1))]
-        pub fn update_metadata(
+        #[pallet::call_index(16)]
+        #[pallet::weight(Weight::from_parts(15_000, 0) + T::DbWeight::get().reads_writes(
+            9,
+            19 + 3 * T::MaxReapBatch::get() as u64 + T::MaxTiers::get() as u64 +
+                T::MaxTags::get() as u64,
+        ))]
+        pub fn reap_campaign(
             origin: OriginFor<T>,
             campaign_id: CampaignId,
-            metadata: Metadata<T>,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            
+            let campaign = Campaigns::<T>::get(campaign_id).ok_or(Error::<T>::CampaignNotFound)?;
+            ensure!(campaign.status == CampaignStatus::Settled, Error::<T>::NotSettled);
+            let settled_at = SettledAt::<T>::get(campaign_id).ok_or(Error::<T>::NotSettled)?;
+            ensure!(
+                T::Timestamp::now() >= settled_at.saturating_add(T::RetentionPeriod::get()),
+                Error::<T>::RetentionPeriodNotOver
+            );
+            // The challenger's bond must be resolved before the campaign disappears
+            ensure!(!Challenges::<T>::contains_key(campaign_id), Error::<T>::AlreadyChallenged);
+            // Its match is only known once the round is finalized
+            if let Some(round_id) = CampaignRound::<T>::get(campaign_id) {
+                ensure!(
+                    Rounds::<T>::get(round_id).is_none_or(|round| round.status == RoundStatus::Finalized),
+                    Error::<T>::RoundNotFinalized
+                );
+            }
+            
+            // Release commitments nobody revealed or withdrew before removing contributions
+            let mut released = 0u32;
+            let limit = T::MaxReapBatch::get();
+            for (who, commitment) in Commitments::<T>::drain_prefix(campaign_id).take(limit as usize) {
+                T::Currency::unreserve(&who, commitment.bound);
+                released = released.saturating_add(1);
+            }
+            if released == limit && Commitments::<T>::iter_key_prefix(campaign_id).next().is_some() {
+                return Ok(());
+            }
+            
+            let removed = TierSelections::<T>::drain_prefix(campaign_id).take(limit as usize).count();
+            if removed == limit as usize && TierSelections::<T>::iter_key_prefix(campaign_id).next().is_some() {
+                return Ok(());
+            }
+            
+            let cursor = ReapCursors::<T>::take(campaign_id);
+            let result = CampaignContributions::<T>::clear_prefix(
+                campaign_id,
+                T::MaxReapBatch::get(),
+                cursor.as_ref().map(|cursor| &cursor[..]),
+            );
+            if let Some(cursor) = result.maybe_cursor {
+                let cursor: CursorOf = cursor.try_into().map_err(|_| Error::<T>::CursorTooLong)?;
+                ReapCursors::<T>::insert(campaign_id, cursor);
+                return Ok(());
+            }
+            
+            // Rejected applications to other rounds stay as the record of the curator's reasons
+            let matching = match CampaignRound::<T>::take(campaign_id) {
+                Some(round_id) => {
+                    RoundApplications::<T>::remove(round_id, campaign_id);
+                    RoundCampaigns::<T>::mutate(round_id, |campaigns| campaigns.retain(|id| *id != campaign_id));
+                    RoundMatches::<T>::take(round_id, campaign_id)
+                },
+                None => Zero::zero(),
+            };
+            T::Currency::unreserve(&campaign.owner, campaign.deposit);
+            
+            Self::delist(campaign_id, &campaign.metadata);
+            DelistedCampaigns::<T>::remove(campaign_id);
+            CampaignsByOwner::<T>::remove(&campaign.owner, campaign_id);
+            Campaigns::<T>::remove(campaign_id);
+            SettledAt::<T>::remove(campaign_id);
+            CampaignTiers::<T>::remove(campaign_id);
+            CampaignRootSums::<T>::remove(campaign_id);
+            CampaignRefunds::<T>::remove(campaign_id);
+            MetadataRevisions::<T>::remove(campaign_id);
+            StretchGoals::<T>::remove(campaign_id);
+            ReachedStretchGoals::<T>::remove(campaign_id);
+            CampaignExtensions::<T>::remove(campaign_id);
+            AutoExtensions::<T>::remove(campaign_id);
+            let _ = TierBackers::<T>::clear_prefix(campaign_id, T::MaxTiers::get(), None);
+            CampaignHistory::<T>::insert(campaign_id, CampaignSummary {
+                owner: campaign.owner,
+                raised: campaign.matched,
+                contributors: campaign.contributors,
+                matching,
+                settled_at,
+            });
+            
+            Self::deposit_event(Event::CampaignReaped { campaign_id });
+            Ok(())
+        }
+
+        #[pallet::call_index(17)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 2))]
+        pub fn create_round(
+            origin: OriginFor<T>,
+            start: MomentOf<T>,
+            end: MomentOf<T>,
+            reveal_window: Option<MomentOf<T>>,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            
+            ensure!(start < end, Error::<T>::InvalidTimeRange);
+            ensure!(T::Timestamp::now() < start, Error::<T>::InvalidTimeRange);
+            
+            let round_id = NextRoundId::<T>::get();
+            Rounds::<T>::insert(round_id, Round {
+                start,
+                end,
+                matching_pool: Zero::zero(),
+                match_cap: Parameters::<T>::get().match_cap,
+                curator: None,
+                status: RoundStatus::Open,
+                reveal_window,
+            });
+            NextRoundId::<T>::put(round_id.saturating_add(1));
+            
+            Self::deposit_event(Event::RoundCreated { round_id, start, end });
+            Ok(())
+        }
+
+        #[pallet::call_index(18)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 2))]
+        pub fn fund_round(
+            origin: OriginFor<T>,
+            round_id: RoundId,
+            amount: BalanceOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            
+            Rounds::<T>::try_mutate(round_id, |maybe_round| -> DispatchResult {
+                let round = maybe_round.as_mut().ok_or(Error::<T>::RoundNotFound)?;
+                ensure!(round.status == RoundStatus::Open, Error::<T>::RoundAlreadyFinalized);
+                
+                T::Currency::transfer(&who, &Self::account_id(), amount, ExistenceRequirement::KeepAlive)?;
+                round.matching_pool = round.matching_pool.saturating_add(amount);
+                
+                Self::deposit_event(Event::RoundFunded { round_id, who, amount });
+                Ok(())
+            })
+        }
+
+        #[pallet::call_index(19)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 3))]
+        pub fn fund_round_from_treasury(
+            origin: OriginFor<T>,
+            round_id: RoundId,
+            amount: BalanceOf<T>,
+        ) -> DispatchResult {
+            let max_amount = T::TreasurySpendOrigin::ensure_origin(origin)?;
+            
+            // The limit covers the whole period, so repeated calls cannot add up past it
+            let spend_period = T::TreasurySpendPeriod::get();
+            let period = if spend_period.is_zero() {
+                Zero::zero()
+            } else {
+                T::Timestamp::now() / spend_period
+            };
+            let (last_period, spent) = TreasurySpent::<T>::get();
+            let spent = if last_period == period { spent } else { Zero::zero() };
+            let spent = spent.saturating_add(amount);
+            ensure!(spent <= max_amount, Error::<T>::InsufficientPermission);
+            
+            Rounds::<T>::try_mutate(round_id, |maybe_round| -> DispatchResult {
+                let round = maybe_round.as_mut().ok_or(Error::<T>::RoundNotFound)?;
+                ensure!(round.status == RoundStatus::Open, Error::<T>::RoundAlreadyFinalized);
+                
+                let treasury = T::TreasuryAccount::get();
+                T::Currency::transfer(&treasury, &Self::account_id(), amount, ExistenceRequirement::KeepAlive)?;
+                round.matching_pool = round.matching_pool.saturating_add(amount);
+                TreasurySpent::<T>::put((period, spent));
+                
+                Self::deposit_event(Event::RoundFunded { round_id, who: treasury, amount });
+                Ok(())
+            })
+        }
+
+        #[pallet::call_index(20)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(6, 2))]
+        pub fn apply_to_round(
+            origin: OriginFor<T>,
+            round_id: RoundId,
+            campaign_id: CampaignId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            
+            let campaign = Campaigns::<T>::get(campaign_id).ok_or(Error::<T>::CampaignNotFound)?;
+            ensure!(campaign.owner == who, Error::<T>::NotOwner);
+            ensure!(
+                campaign.status == CampaignStatus::Upcoming || campaign.status == CampaignStatus::Active,
+                Error::<T>::AlreadyFinalized
+            );
+            
+            let round = Rounds::<T>::get(round_id).ok_or(Error::<T>::RoundNotFound)?;
+            ensure!(round.status == RoundStatus::Open, Error::<T>::RoundAlreadyFinalized);
+            ensure!(
+                T::Timestamp::now() < round.start.saturating_sub(T::ApplicationCutoff::get()),
+                Error::<T>::ApplicationsClosed
+            );
+            ensure!(campaign.end <= round.end, Error::<T>::CampaignOutsideRound);
+            // Contributions made in public or to another round cannot be sealed after the fact
+            ensure!(
+                round.reveal_window.is_none() || (
+                    CampaignContributions::<T>::iter_key_prefix(campaign_id).next().is_none() &&
+                        Commitments::<T>::iter_key_prefix(campaign_id).next().is_none()
+                ),
+                Error::<T>::CampaignHasContributions
+            );
+            ensure!(!CampaignRound::<T>::contains_key(campaign_id), Error::<T>::AlreadyApplied);
+            // A rejection is final for that round and must not be overwritten
+            ensure!(
+                !RoundApplications::<T>::contains_key(round_id, campaign_id),
+                Error::<T>::AlreadyApplied
+            );
+            
+            RoundApplications::<T>::insert(round_id, campaign_id, ApplicationStatus::Pending);
+            CampaignRound::<T>::insert(campaign_id, round_id);
+            
+            Self::deposit_event(Event::ApplicationSubmitted { round_id, campaign_id });
+            Ok(())
+        }
+
+        #[pallet::call_index(21)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 2))]
+        pub fn approve_application(
+            origin: OriginFor<T>,
+            round_id: RoundId,
+            campaign_id: CampaignId,
+        ) -> DispatchResult {
+            let round = Rounds::<T>::get(round_id).ok_or(Error::<T>::RoundNotFound)?;
+            Self::ensure_curator(origin, &round)?;
+            // Cancelled or finished campaigns must not take up one of the round's slots
+            let campaign = Campaigns::<T>::get(campaign_id).ok_or(Error::<T>::CampaignNotFound)?;
+            ensure!(
+                matches!(
+                    campaign.status,
+                    CampaignStatus::Upcoming | CampaignStatus::Active | CampaignStatus::Paused
+                ),
+                Error::<T>::AlreadyFinalized
+            );
+            
+            RoundApplications::<T>::try_mutate(round_id, campaign_id, |maybe_status| -> DispatchResult {
+                let status = maybe_status.as_mut().ok_or(Error::<T>::ApplicationNotFound)?;
+                ensure!(*status == ApplicationStatus::Pending, Error::<T>::ApplicationNotPending);
+                
+                RoundCampaigns::<T>::try_mutate(round_id, |campaigns| {
+                    campaigns.try_push(campaign_id)
+                }).map_err(|_| Error::<T>::TooManyRoundCampaigns)?;
+                
+                *status = ApplicationStatus::Approved;
+                Self::deposit_event(Event::ApplicationApproved { round_id, campaign_id });
+                Ok(())
+            })
+        }
+
+        #[pallet::call_index(22)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 2))]
+        pub fn reject_application(
+            origin: OriginFor<T>,
+            round_id: RoundId,
+            campaign_id: CampaignId,
+            reason: T::Hash,
+        ) -> DispatchResult {
+            let round = Rounds::<T>::get(round_id).ok_or(Error::<T>::RoundNotFound)?;
+            Self::ensure_curator(origin, &round)?;
+            
+            RoundApplications::<T>::try_mutate(round_id, campaign_id, |maybe_status| -> DispatchResult {
+                let status = maybe_status.as_mut().ok_or(Error::<T>::ApplicationNotFound)?;
+                ensure!(*status == ApplicationStatus::Pending, Error::<T>::ApplicationNotPending);
+                
+                *status = ApplicationStatus::Rejected { reason };
+                // A rejected campaign is free to apply to another round
+                CampaignRound::<T>::remove(campaign_id);
+                
+                Self::deposit_event(Event::ApplicationRejected { round_id, campaign_id, reason });
+                Ok(())
+            })
+        }
+
+        #[pallet::call_index(23)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 1))]
+        pub fn set_round_coordinator(
+            origin: OriginFor<T>,
+            round_id: RoundId,
+            coordinator: T::AccountId,
+            public_key: PublicKeyOf<T>,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            ensure!(T::TallyVerifier::ENABLED, Error::<T>::TallyVerificationDisabled);
+            
+            let round = Rounds::<T>::get(round_id).ok_or(Error::<T>::RoundNotFound)?;
+            ensure!(round.status == RoundStatus::Open, Error::<T>::RoundAlreadyFinalized);
+            // Messages already encrypted to the old key could not be tallied
+            ensure!(T::Timestamp::now() < round.start, Error::<T>::CoordinatorLocked);
+            
+            Coordinations::<T>::try_mutate(round_id, |maybe_coordination| -> DispatchResult {
+                match maybe_coordination {
+                    Some(coordination) => {
+                        ensure!(coordination.signups == 0, Error::<T>::CoordinatorLocked);
+                        coordination.coordinator = coordinator.clone();
+                        coordination.public_key = public_key;
+                    },
+                    None => {
+                        *maybe_coordination = Some(Coordination {
+                            coordinator: coordinator.clone(),
+                            public_key,
+                            signups: 0,
+                            signup_chain: Default::default(),
+                            messages: 0,
+                            message_chain: Default::default(),
+                            budget: Zero::zero(),
+                        });
+                    },
+                }
+                Ok(())
+            })?;
+            
+            Self::deposit_event(Event::CoordinatorSet { round_id, coordinator });
+            Ok(())
+        }
+
+        #[pallet::call_index(24)]
+        #[pallet::weight(Weight::from_parts(15_000, 0) + T::DbWeight::get().reads_writes(4, 4))]
+        pub fn signup(
+            origin: OriginFor<T>,
+            round_id: RoundId,
+            public_key: PublicKeyOf<T>,
+            budget: BalanceOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            
+            let round = Rounds::<T>::get(round_id).ok_or(Error::<T>::RoundNotFound)?;
+            ensure!(T::Timestamp::now() < round.end, Error::<T>::OutsideVotingPeriod);
+            ensure!(
+                budget >= Parameters::<T>::get().min_contribution,
+                Error::<T>::ContributionTooLow
+            );
+            
+            Coordinations::<T>::try_mutate(round_id, |maybe_coordination| -> DispatchResult {
+                let coordination = maybe_coordination.as_mut().ok_or(Error::<T>::NotCoordinated)?;
+                
+                RoundSignups::<T>::try_mutate(round_id, |signups| signups.try_push((who.clone(), budget)))
+                    .map_err(|_| Error::<T>::TooManySignups)?;
+                // The budget is only allocated by the tally, so it moves to the pot right away
+                T::Currency::transfer(&who, &Self::account_id(), budget, ExistenceRequirement::KeepAlive)?;
+                
+                let index = coordination.signups;
+                coordination.signup_chain =
+                    T::Hashing::hash_of(&(coordination.signup_chain, &who, &public_key, budget));
+                coordination.signups = index.saturating_add(1);
+                coordination.budget = coordination.budget.saturating_add(budget);
+                
+                Self::deposit_event(Event::SignedUp { round_id, who, index, public_key, budget });
+                Ok(())
+            })
+        }
+
+        #[pallet::call_index(25)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 1))]
+        pub fn publish_message(
+            origin: OriginFor<T>,
+            round_id: RoundId,
+            message: MessageOf<T>,
+        ) -> DispatchResult {
+            // Messages are authenticated by the key inside them, not by the sender
+            ensure_signed(origin)?;
+            
+            let round = Rounds::<T>::get(round_id).ok_or(Error::<T>::RoundNotFound)?;
+            let now = T::Timestamp::now();
+            ensure!(now >= round.start && now < round.end, Error::<T>::OutsideVotingPeriod);
+            
+            Coordinations::<T>::try_mutate(round_id, |maybe_coordination| -> DispatchResult {
+                let coordination = maybe_coordination.as_mut().ok_or(Error::<T>::NotCoordinated)?;
+                
+                let index = coordination.messages;
+                coordination.message_chain = T::Hashing::hash_of(&(coordination.message_chain, &message));
+                coordination.messages = index.saturating_add(1);
+                
+                Self::deposit_event(Event::MessagePublished { round_id, index, message });
+                Ok(())
+            })
+        }
+
+        #[pallet::call_index(26)]
+        #[pallet::weight(Weight::from_parts(50_000, 0) + T::DbWeight::get().reads_writes(4, 1))]
+        pub fn submit_tally(
+            origin: OriginFor<T>,
+            round_id: RoundId,
+            tally: BoundedVec<TallyEntry<BalanceOf<T>>, T::MaxRoundCampaigns>,
+            proof: ProofOf<T>,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
             
-            Campaigns::<T>::try_mutate(campaign_id, |maybe_campaign| -> DispatchResult {
-                let campaign = maybe_campaign.as_mut().ok_or(Error::<T>::CampaignNotFound)?;
-                ensure!(campaign.owner == who, Error::<T>::NotOwner);
-                ensure!(campaign.status == CampaignStatus::Upcoming, Error::<T>::NotActive);
+            let round = Rounds::<T>::get(round_id).ok_or(Error::<T>::RoundNotFound)?;
+            ensure!(round.status == RoundStatus::Open, Error::<T>::RoundAlreadyFinalized);
+            ensure!(T::Timestamp::now() >= round.end, Error::<T>::RoundNotEnded);
+            let coordination = Coordinations::<T>::get(round_id).ok_or(Error::<T>::NotCoordinated)?;
+            ensure!(coordination.coordinator == who, Error::<T>::NotCoordinator);
+            ensure!(!RoundTallies::<T>::contains_key(round_id), Error::<T>::TallyAlreadySubmitted);
+            
+            // Every entry is an approved campaign, listed once, and the budget is not overspent
+            let approved = RoundCampaigns::<T>::get(round_id);
+            let mut seen = alloc::collections::BTreeSet::new();
+            let mut spent = BalanceOf::<T>::zero();
+            for entry in tally.iter() {
+                ensure!(
+                    approved.contains(&entry.campaign_id) && seen.insert(entry.campaign_id),
+                    Error::<T>::InvalidTally
+                );
+                spent = spent.saturating_add(entry.spent);
+            }
+            ensure!(spent <= coordination.budget, Error::<T>::InvalidTally);
+            
+            let inputs = TallyInputs {
+                coordinator_key: coordination.public_key,
+                signup_chain: coordination.signup_chain,
+                signups: coordination.signups,
+                message_chain: coordination.message_chain,
+                messages: coordination.messages,
+                budget: coordination.budget,
+            };
+            ensure!(T::TallyVerifier::verify(&inputs, &tally, &proof), Error::<T>::InvalidTally);
+            
+            RoundTallies::<T>::insert(round_id, tally);
+            
+            Self::deposit_event(Event::TallySubmitted { round_id });
+            Ok(())
+        }
+
+        #[pallet::call_index(27)]
+        #[pallet::weight(Weight::from_parts(20_000, 0) + T::DbWeight::get().reads_writes(
+            7 + 8 * T::MaxRoundCampaigns::get() as u64 + T::MaxSignups::get() as u64,
+            4 + 3 * T::MaxRoundCampaigns::get() as u64 + T::MaxSignups::get() as u64,
+        ))]
+        pub fn finalize_round(
+            origin: OriginFor<T>,
+            round_id: RoundId,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            
+            let mut round = Rounds::<T>::get(round_id).ok_or(Error::<T>::RoundNotFound)?;
+            ensure!(round.status == RoundStatus::Open, Error::<T>::RoundAlreadyFinalized);
+            let now = T::Timestamp::now();
+            ensure!(now >= round.end, Error::<T>::RoundNotEnded);
+            // Matching reads final contributions, including those revealed after the round's end
+            for campaign_id in RoundCampaigns::<T>::get(round_id) {
+                let Some(campaign) = Campaigns::<T>::get(campaign_id) else {
+                    continue;
+                };
+                ensure!(
+                    !matches!(
+                        campaign.status,
+                        CampaignStatus::Upcoming | CampaignStatus::Active | CampaignStatus::Paused
+                    ) && now >= Self::reveal_deadline(campaign_id, &campaign),
+                    Error::<T>::RoundCampaignsPending
+                );
+                // A dismissed challenge would otherwise cost the campaign its match for good
+                ensure!(
+                    !Challenges::<T>::contains_key(campaign_id),
+                    Error::<T>::RoundCampaignsChallenged
+                );
+            }
+            
+            let mut pool = round.matching_pool;
+            if let Some(coordination) = Coordinations::<T>::get(round_id) {
+                // Without a tally past the timeout nothing is spent and matching falls back to
+                // contributions, so a missing coordinator cannot lock the round
+                let tally = match RoundTallies::<T>::get(round_id) {
+                    Some(tally) => tally,
+                    None => {
+                        ensure!(
+                            now >= round.end.saturating_add(T::TallyTimeout::get()),
+                            Error::<T>::TallyMissing
+                        );
+                        Default::default()
+                    },
+                };
+                let mut paid = BalanceOf::<T>::zero();
+                for entry in tally {
+                    if entry.spent.is_zero() || !Self::match_eligible(entry.campaign_id) {
+                        continue;
+                    }
+                    let campaign = Campaigns::<T>::get(entry.campaign_id).ok_or(Error::<T>::CampaignNotFound)?;
+                    T::Currency::transfer(
+                        &Self::account_id(),
+                        &campaign.owner,
+                        entry.spent,
+                        ExistenceRequirement::AllowDeath,
+                    )?;
+                    paid = paid.saturating_add(entry.spent);
+                    
+                    Self::deposit_event(Event::TallyAllocated {
+                        round_id,
+                        campaign_id: entry.campaign_id,
+                        amount: entry.spent,
+                    });
+                }
+                
+                // Budget not paid to an eligible campaign belongs to the signups, not the sponsors,
+                // so it is returned in proportion to each budget before anything is swept
+                let unspent = coordination.budget.saturating_sub(paid);
+                let mut refunded = BalanceOf::<T>::zero();
+                for (who, budget) in RoundSignups::<T>::take(round_id) {
+                    let amount = Perbill::from_rational(budget, coordination.budget).mul_floor(unspent);
+                    if amount.is_zero() {
+                        continue;
+                    }
+                    T::Currency::transfer(&Self::account_id(), &who, amount, ExistenceRequirement::AllowDeath)?;
+                    refunded = refunded.saturating_add(amount);
+                    
+                    Self::deposit_event(Event::SignupRefunded { round_id, who, amount });
+                }
+                // Rounding dust is swept with the pool
+                pool = pool.saturating_add(unspent.saturating_sub(refunded));
+            }
+            
+            let matches = Self::quadratic_match(round_id, pool, round.match_cap);
+            for (campaign_id, amount) in matches {
+                if amount.is_zero() {
+                    continue;
+                }
+                let campaign = Campaigns::<T>::get(campaign_id).ok_or(Error::<T>::CampaignNotFound)?;
+                T::Currency::transfer(
+                    &Self::account_id(),
+                    &campaign.owner,
+                    amount,
+                    ExistenceRequirement::AllowDeath,
+                )?;
+                RoundMatches::<T>::insert(round_id, campaign_id, amount);
+                pool = pool.saturating_sub(amount);
+                
+                Self::deposit_event(Event::MatchAllocated { round_id, campaign_id, amount });
+            }
+            
+            // Whatever the match cap, rounding or a lack of eligible campaigns left over goes to
+            // the treasury rather than staying in the pallet account
+            if !pool.is_zero() {
+                let treasury = T::TreasuryAccount::get();
+                T::Currency::transfer(&Self::account_id(), &treasury, pool, ExistenceRequirement::AllowDeath)?;
+                
+                Self::deposit_event(Event::RoundSwept { round_id, amount: pool });
+            }
+            
+            round.status = RoundStatus::Finalized;
+            Rounds::<T>::insert(round_id, round);
+            
+            Self::deposit_event(Event::RoundFinalized { round_id });
+            Ok(())
+        }
+
+        #[pallet::call_index(28)]
+        #[pallet::weight(Weight::from_parts(5_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_round_curator(
+            origin: OriginFor<T>,
+            round_id: RoundId,
+            curator: Option<T::AccountId>,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            
+            Rounds::<T>::try_mutate(round_id, |maybe_round| -> DispatchResult {
+                let round = maybe_round.as_mut().ok_or(Error::<T>::RoundNotFound)?;
+                ensure!(round.status == RoundStatus::Open, Error::<T>::RoundAlreadyFinalized);
                 
-                campaign.metadata = metadata;
-                Self::deposit_event(Event::MetadataUpdated { campaign_id });
+                round.curator = curator.clone();
+                Self::deposit_event(Event::RoundCuratorSet { round_id, curator });
                 Ok(())
             })
         }
 
-        #[pallet::weight(5_000 + T::DbWeight::get().reads_writes(1, 1))]
-        pub fn set_caps(
+        #[pallet::call_index(29)]
+        #[pallet::weight(Weight::from_parts(5_000, 0) + T::DbWeight::get().writes(1))]
+        pub fn set_parameters(
             origin: OriginFor<T>,
-            campaign_id: CampaignId,
-            soft_cap: BalanceOf<T>,
-            hard_cap: BalanceOf<T>,
+            parameters: ParametersOf<T>,
         ) -> DispatchResult {
-            let who = ensure_signed(origin)?;
+            T::ParameterOrigin::ensure_origin(origin)?;
             
-            ensure!(soft_cap <= hard_cap, Error::<T>::CapsInvalid);
-            ensure!(
-                !soft_cap.is_zero() && !hard_cap.is_zero(),
-                Error::<T>::CapsInvalid
-            );
+            Parameters::<T>::put(parameters.clone());
             
-            Campaigns::<T>::try_mutate(campaign_id, |maybe_campaign| -> DispatchResult {
-                let campaign = maybe_campaign.as_mut().ok_or(Error::<T>::CampaignNotFound)?;
-                ensure!(campaign.owner == who, Error::<T>::NotOwner);
-                ensure!(campaign.status == CampaignStatus::Upcoming, Error::<T>::NotActive);
-                
-                campaign.soft_cap = soft_cap;
-                campaign.hard_cap = hard_cap;
-                
-                Self::deposit_event(Event::CapsUpdated {
-                    campaign_id,
-                    soft_cap,
-                    hard_cap,
-                });
-                Ok(())
-            })
+            Self::deposit_event(Event::ParametersUpdated { parameters });
+            Ok(())
         }
 
-        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 2))]
-        pub fn cancel_campaign(
+        #[pallet::call_index(30)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 2))]
+        pub fn challenge_campaign(
             origin: OriginFor<T>,
             campaign_id: CampaignId,
+            evidence_hash: T::Hash,
         ) -> DispatchResult {
-            let who = ensure_signed(origin)?;
+            let challenger = ensure_signed(origin)?;
             
-            Campaigns::<T>::try_mutate(campaign_id, |maybe_campaign| -> DispatchResult {
-                let campaign = maybe_campaign.as_mut().ok_or(Error::<T>::CampaignNotFound)?;
-                ensure!(
-                    campaign.owner == who || frame_system::Pallet::<T>::is_root(origin.clone()),
-                    Error::<T>::NotOwner
-                );
-                ensure!(
-                    campaign.status == CampaignStatus::Upcoming || campaign.status == CampaignStatus::Active,
-                    Error::<T>::AlreadyFinalized
-                );
-                
-                campaign.status = CampaignStatus::Cancelled;
-                
-                // Remove from active campaigns if needed
-                if campaign.status == CampaignStatus::Active {
-                    ActiveCampaigns::<T>::try_mutate(|campaigns| {
-                        campaigns.retain(|id| *id != campaign_id);
-                        Ok(())
-                    })?;
-                }
-                
-                // Unreserve the deposit for the owner
-                T::Currency::unreserve(&campaign.owner, T::MinimumDeposit::get());
-                
-                Self::deposit_event(Event::CampaignCancelled { campaign_id });
-                Ok(())
-            })
+            let campaign = Campaigns::<T>::get(campaign_id).ok_or(Error::<T>::CampaignNotFound)?;
+            ensure!(
+                campaign.status != CampaignStatus::Cancelled && campaign.status != CampaignStatus::Settled,
+                Error::<T>::AlreadyFinalized
+            );
+            ensure!(!Challenges::<T>::contains_key(campaign_id), Error::<T>::AlreadyChallenged);
+            // Part of the contributions may already have been paid out
+            ensure!(
+                !SettlementCursors::<T>::contains_key(campaign_id),
+                Error::<T>::SettlementInProgress
+            );
+            
+            let bond = Parameters::<T>::get().challenge_bond;
+            T::Currency::reserve(&challenger, bond)?;
+            
+            Challenges::<T>::insert(campaign_id, Challenge {
+                challenger: challenger.clone(),
+                bond,
+                evidence: evidence_hash,
+            });
+            
+            Self::deposit_event(Event::CampaignChallenged {
+                campaign_id,
+                challenger,
+                evidence: evidence_hash,
+            });
+            Ok(())
         }
 
-        #[pallet::weight(15_000 + T::DbWeight::get().reads_writes(2, 2))]
-        pub fn contribute(
+        #[pallet::call_index(31)]
+        #[pallet::weight(Weight::from_parts(15_000, 0) + T::DbWeight::get().reads_writes(3, 4))]
+        pub fn resolve_challenge(
             origin: OriginFor<T>,
             campaign_id: CampaignId,
-            amount: BalanceOf<T>,
+            uphold: bool,
         ) -> DispatchResult {
-            let who = ensure_signed(origin)?;
+            T::AdminOrigin::ensure_origin(origin)?;
             
-            Campaigns::<T>::try_mutate(campaign_id, |maybe_campaign| -> DispatchResult {
-                let campaign = maybe_campaign.as_mut().ok_or(Error::<T>::CampaignNotFound)?;
-                ensure!(campaign.status == CampaignStatus::Active, Error::<T>::NotActive);
+            let challenge = Challenges::<T>::take(campaign_id).ok_or(Error::<T>::NoChallenge)?;
+            
+            if uphold {
+                let mut campaign = Campaigns::<T>::get(campaign_id).ok_or(Error::<T>::CampaignNotFound)?;
                 
-                let new_total = campaign.matched.saturating_add(amount);
-                ensure!(new_total <= campaign.hard_cap, Error::<T>::HardCapExceeded);
+                // Pay the owner's deposit to the challenger and return the bond
+                let remaining = T::Currency::repatriate_reserved(
+                    &campaign.owner,
+                    &challenge.challenger,
+                    campaign.deposit,
+                    BalanceStatus::Free,
+                )?;
+                let reward = campaign.deposit.saturating_sub(remaining);
+                T::Currency::unreserve(&challenge.challenger, challenge.bond);
                 
-                // Reserve the contribution
-                T::Currency::reserve(&who, amount)?;
+                // Fraudulent campaigns are cancelled so contributors can claim refunds
+                if campaign.status != CampaignStatus::Cancelled && campaign.status != CampaignStatus::Settled {
+                    Self::do_cancel(campaign_id, &mut campaign);
+                }
+                campaign.deposit = Zero::zero();
+                Campaigns::<T>::insert(campaign_id, campaign);
                 
-                // Update contribution tracking
-                CampaignContributions::<T>::try_mutate(
+                Self::deposit_event(Event::ChallengeUpheld {
                     campaign_id,
-                    who.clone(),
-                    |contribution| -> DispatchResult {
-                        *contribution = contribution.saturating_add(amount);
-                        Ok(())
-                    }
-                )?;
-                
-                campaign.matched = new_total;
+                    challenger: challenge.challenger,
+                    reward,
+                });
+            } else {
+                let (imbalance, _) = T::Currency::slash_reserved(&challenge.challenger, challenge.bond);
+                let slashed = imbalance.peek();
+                T::Slashed::on_unbalanced(imbalance);
                 
-                Self::deposit_event(Event::ContributionMade {
+                Self::deposit_event(Event::ChallengeDismissed {
                     campaign_id,
-                    who,
-                    amount,
+                    challenger: challenge.challenger,
+                    slashed,
                 });
-                Ok(())
-            })
+            }
+            Ok(())
         }
+    }
 
-        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 2))]
-        pub fn claim_refund(
-            origin: OriginFor<T>,
+    impl<T: Config> Pallet<T> {
+        /// The account holding the matching pools of all rounds.
+        pub fn account_id() -> T::AccountId {
+            T::PalletId::get().into_account_truncating()
+        }
+
+        /// Mark a campaign as cancelled, dropping it from the active set.
+        ///
+        /// Contributions stay reserved and become claimable through `claim_refund`. The caller is
+        /// responsible for the owner's deposit.
+        fn do_cancel(campaign_id: CampaignId, campaign: &mut Campaign<T>) {
+            if matches!(campaign.status, CampaignStatus::Active | CampaignStatus::Paused) {
+                ActiveCampaigns::<T>::mutate(|campaigns| campaigns.retain(|id| *id != campaign_id));
+            }
+            PausedAt::<T>::remove(campaign_id);
+            Self::delist(campaign_id, &campaign.metadata);
+            DelistedCampaigns::<T>::insert(campaign_id, ());
+            campaign.status = CampaignStatus::Cancelled;
+        }
+
+        /// Run `OnContribution` with the round and total contribution of `who`.
+        fn notify_contribution(campaign_id: CampaignId, who: &T::AccountId) {
+            T::OnContribution::on_contribution(
+                campaign_id,
+                CampaignRound::<T>::get(campaign_id),
+                who,
+                CampaignContributions::<T>::get(campaign_id, who),
+            );
+        }
+
+        /// Select reward tier `tier_id` for `who`, who has contributed `total` to the campaign.
+        ///
+        /// A contributor holds one tier at a time, so switching frees their place in the old one.
+        fn select_tier(
             campaign_id: CampaignId,
+            who: &T::AccountId,
+            tier_id: TierId,
+            total: BalanceOf<T>,
         ) -> DispatchResult {
-            let who = ensure_signed(origin)?;
+            let tiers = CampaignTiers::<T>::get(campaign_id);
+            let tier = tiers.get(tier_id as usize).ok_or(Error::<T>::TierNotFound)?;
+            ensure!(total >= tier.min_amount, Error::<T>::BelowTierMinimum);
             
-            let campaign = Campaigns::<T>::get(campaign_id).ok_or(Error::<T>::CampaignNotFound)?;
-            ensure!(
-                campaign.status == CampaignStatus::Failed || campaign.status == CampaignStatus::Cancelled,
-                Error::<T>::NotRefundable
-            );
+            let previous = TierSelections::<T>::get(campaign_id, who);
+            if previous == Some(tier_id) {
+                return Ok(());
+            }
+            TierBackers::<T>::try_mutate(campaign_id, tier_id, |backers| -> DispatchResult {
+                ensure!(tier.supply.is_none_or(|supply| *backers < supply), Error::<T>::TierSoldOut);
+                *backers = backers.saturating_add(1);
+                Ok(())
+            })?;
+            if let Some(previous) = previous {
+                TierBackers::<T>::mutate(campaign_id, previous, |backers| {
+                    *backers = backers.saturating_sub(1)
+                });
+            }
+            TierSelections::<T>::insert(campaign_id, who, tier_id);
             
-            let contribution = CampaignContributions::<T>::take(campaign_id, who.clone());
-            ensure!(!contribution.is_zero(), Error::<T>::NoContributionFound);
+            Self::deposit_event(Event::TierSelected { campaign_id, who: who.clone(), tier_id });
+            Ok(())
+        }
+
+        /// Ensure `metadata` has a printable name and a well-formed link, lists every tag once,
+        /// references well-formed content and passes the runtime's `MetadataValidator`.
+        fn ensure_metadata(metadata: &Metadata<T>) -> DispatchResult {
+            let name = core::str::from_utf8(&metadata.name).map_err(|_| Error::<T>::NameNotUtf8)?;
+            ensure!(!name.trim().is_empty(), Error::<T>::EmptyName);
+            ensure!(!name.chars().any(char::is_control), Error::<T>::NameHasControlCharacters);
+            if let Some(link) = &metadata.link {
+                link::validate(link).map_err(|error| match error {
+                    link::LinkError::UnsupportedScheme => Error::<T>::UnsupportedLinkScheme,
+                    link::LinkError::Malformed => Error::<T>::MalformedLink,
+                })?;
+            }
             
-            // Unreserve and transfer the contribution back
-            T::Currency::unreserve(&who, contribution);
+            let mut tags = metadata.tags.clone().into_inner();
+            tags.sort_unstable();
+            tags.dedup();
+            ensure!(tags.len() == metadata.tags.len(), Error::<T>::DuplicateTag);
+            if let Some(content) = &metadata.content {
+                ensure!(cid::is_valid(&content.cid), Error::<T>::InvalidCid);
+                ensure!(cid::is_valid_mime_type(&content.mime_type), Error::<T>::InvalidMimeType);
+            }
+            T::MetadataValidator::validate(metadata)
+        }
+
+        /// Append a revision with metadata hash `hash` to the log of a campaign, dropping the
+        /// oldest revision once `MaxRevisions` are kept, and return its number.
+        fn log_revision(campaign_id: CampaignId, hash: T::Hash) -> u32 {
+            MetadataRevisions::<T>::mutate(campaign_id, |revisions| {
+                let number = revisions.last().map_or(0, |last| last.number.saturating_add(1));
+                let revision = Revision {
+                    number,
+                    block: frame_system::Pallet::<T>::block_number(),
+                    hash,
+                };
+                // Drop the oldest revision to make room for the new one
+                if revisions.is_full() && !revisions.is_empty() {
+                    revisions.remove(0);
+                }
+                // Only fails when `MaxRevisions` is zero and no log is kept at all
+                let _ = revisions.try_push(revision);
+                number
+            })
+        }
+
+        /// Deposit of a campaign storing `metadata`, growing with its encoded size.
+        pub fn metadata_deposit(metadata: &Metadata<T>) -> BalanceOf<T> {
+            let bytes = BalanceOf::<T>::from(metadata.encoded_size() as u32);
+            T::DepositBase::get().saturating_add(T::DepositPerByte::get().saturating_mul(bytes))
+        }
+
+        /// Add a campaign to the category and tag indices of `metadata`.
+        fn list(campaign_id: CampaignId, metadata: &Metadata<T>) {
+            CampaignsByCategory::<T>::insert(metadata.category, campaign_id, ());
+            for tag in metadata.tags.iter() {
+                CampaignsByTag::<T>::insert(tag, campaign_id, ());
+            }
+        }
+
+        /// Remove a campaign from the category and tag indices of `metadata`.
+        fn delist(campaign_id: CampaignId, metadata: &Metadata<T>) {
+            CampaignsByCategory::<T>::remove(metadata.category, campaign_id);
+            for tag in metadata.tags.iter() {
+                CampaignsByTag::<T>::remove(tag, campaign_id);
+            }
+        }
+
+        /// Listed campaigns of `category`.
+        pub fn campaigns_by_category(category: Category) -> Vec<CampaignId> {
+            CampaignsByCategory::<T>::iter_key_prefix(category).collect()
+        }
+
+        /// Listed campaigns tagged with `tag`.
+        pub fn campaigns_by_tag(tag: TagId) -> Vec<CampaignId> {
+            CampaignsByTag::<T>::iter_key_prefix(tag).collect()
+        }
+
+        /// Campaigns of `owner` that have not been reaped.
+        pub fn campaigns_by_owner(owner: &T::AccountId) -> Vec<CampaignId> {
+            CampaignsByOwner::<T>::iter_key_prefix(owner).collect()
+        }
+
+        /// The latest end a campaign can be extended to: its original end plus `MaxExtension`, and
+        /// no later than the end of the round it applied to.
+        fn latest_end(campaign_id: CampaignId, campaign: &Campaign<T>) -> MomentOf<T> {
+            let allowance = T::MaxExtension::get()
+                .saturating_sub(CampaignExtensions::<T>::get(campaign_id));
+            let latest = campaign.end.saturating_add(allowance);
+            match CampaignRound::<T>::get(campaign_id).and_then(Rounds::<T>::get) {
+                Some(round) => latest.min(round.end),
+                None => latest,
+            }
+        }
+
+        /// Push the end of an active campaign out to `new_end`, which must not exceed `latest_end`.
+        ///
+        /// `ActiveCampaigns` is ordered by id rather than end, so it needs no update.
+        fn do_extend(campaign_id: CampaignId, campaign: &mut Campaign<T>, new_end: MomentOf<T>) {
+            CampaignExtensions::<T>::mutate(campaign_id, |extended| {
+                *extended = extended.saturating_add(new_end.saturating_sub(campaign.end))
+            });
+            campaign.end = new_end;
+            Self::deposit_event(Event::CampaignExtended { campaign_id, end: new_end });
+        }
+
+        /// Resume a paused campaign. With `extend_end`, its end is first pushed out by the time
+        /// it was paused, as far as `latest_end` allows.
+        fn do_resume(campaign_id: CampaignId, campaign: &mut Campaign<T>, extend_end: bool) {
+            let now = T::Timestamp::now();
+            let paused_at = PausedAt::<T>::take(campaign_id).unwrap_or(now);
+            if extend_end {
+                // Paused time counts toward `MaxExtension` like any other extension
+                let paused = now.saturating_sub(paused_at);
+                let end = campaign
+                    .end
+                    .saturating_add(paused)
+                    .min(Self::latest_end(campaign_id, campaign));
+                if end > campaign.end {
+                    Self::do_extend(campaign_id, campaign, end);
+                }
+            }
+            campaign.status = CampaignStatus::Active;
             
-            Self::deposit_event(Event::RefundClaimed {
-                campaign_id,
-                who,
-                amount: contribution,
+            Self::deposit_event(Event::CampaignResumed { campaign_id, end: campaign.end });
+        }
+
+        /// Whether a campaign that has ended raised enough to pay out under its funding model.
+        fn funding_succeeded(campaign: &Campaign<T>) -> bool {
+            match campaign.funding_model {
+                FundingModel::AllOrNothing => campaign.matched >= campaign.soft_cap,
+                FundingModel::KeepItAll => !campaign.matched.is_zero(),
+                // Small shares of a small soft cap round down to nothing
+                FundingModel::Threshold(share) =>
+                    !campaign.matched.is_zero() && campaign.matched >= share * campaign.soft_cap,
+            }
+        }
+
+        /// Ensure stretch goal targets increase, the first lying above `soft_cap` and within
+        /// `hard_cap` so it can be reached before any headroom is unlocked.
+        fn ensure_stretch_goals(
+            goals: &StretchGoalsOf<T>,
+            soft_cap: BalanceOf<T>,
+            hard_cap: BalanceOf<T>,
+        ) -> DispatchResult {
+            if let Some(first) = goals.first() {
+                ensure!(
+                    first.target > soft_cap && first.target <= hard_cap,
+                    Error::<T>::InvalidStretchGoals
+                );
+            }
+            ensure!(
+                goals.windows(2).all(|pair| pair[0].target < pair[1].target),
+                Error::<T>::InvalidStretchGoals
+            );
+            Ok(())
+        }
+
+        /// Record the stretch goals a campaign has reached with `raised`.
+        fn note_stretch_goals(campaign_id: CampaignId, raised: BalanceOf<T>) {
+            let goals = StretchGoals::<T>::get(campaign_id);
+            ReachedStretchGoals::<T>::mutate(campaign_id, |reached| {
+                while let Some(goal) = goals.get(*reached as usize) {
+                    if raised < goal.target {
+                        break;
+                    }
+                    Self::deposit_event(Event::StretchGoalReached {
+                        campaign_id,
+                        index: *reached,
+                        target: goal.target,
+                    });
+                    *reached = reached.saturating_add(1);
+                }
             });
+        }
+
+        /// Stretch goals the campaign has reached, in order.
+        pub fn reached_stretch_goals(campaign_id: CampaignId) -> Vec<StretchGoal<T>> {
+            let reached = ReachedStretchGoals::<T>::get(campaign_id) as usize;
+            StretchGoals::<T>::get(campaign_id).into_iter().take(reached).collect()
+        }
+
+        /// Contributors who selected a reward tier, with the tier and their contribution.
+        pub fn fulfilment_list(campaign_id: CampaignId) -> Vec<(T::AccountId, TierId, BalanceOf<T>)> {
+            TierSelections::<T>::iter_prefix(campaign_id)
+                .map(|(who, tier_id)| {
+                    let amount = CampaignContributions::<T>::get(campaign_id, &who);
+                    (who, tier_id, amount)
+                })
+                .collect()
+        }
+
+        /// The reveal window of the private round `campaign_id` was approved to, if any.
+        ///
+        /// Campaigns only turn private once approved, so a rejected application strands no
+        /// commitments.
+        pub fn reveal_window(campaign_id: CampaignId) -> Option<MomentOf<T>> {
+            let round_id = CampaignRound::<T>::get(campaign_id)?;
+            if RoundApplications::<T>::get(round_id, campaign_id) != Some(ApplicationStatus::Approved) {
+                return None;
+            }
+            Rounds::<T>::get(round_id).and_then(|round| round.reveal_window)
+        }
+
+        /// The reveal window of the private round `campaign_id` has a pending or approved
+        /// application in, if any.
+        fn applied_reveal_window(campaign_id: CampaignId) -> Option<MomentOf<T>> {
+            CampaignRound::<T>::get(campaign_id)
+                .and_then(Rounds::<T>::get)
+                .and_then(|round| round.reveal_window)
+        }
+
+        /// When an active campaign is finalized: its end, or the end of its reveal window.
+        fn reveal_deadline(campaign_id: CampaignId, campaign: &Campaign<T>) -> MomentOf<T> {
+            Self::reveal_window(campaign_id)
+                .map_or(campaign.end, |window| campaign.end.saturating_add(window))
+        }
+
+        /// Ensure `origin` is the `RoundCuratorOrigin` or the curator appointed for `round`.
+        fn ensure_curator(origin: OriginFor<T>, round: &Round<T>) -> DispatchResult {
+            match T::RoundCuratorOrigin::try_origin(origin) {
+                Ok(_) => Ok(()),
+                Err(origin) => {
+                    let who = ensure_signed(origin)?;
+                    ensure!(round.curator.as_ref() == Some(&who), Error::<T>::NotCurator);
+                    Ok(())
+                }
+            }
+        }
+
+        /// Whether an approved campaign is matched and, in anti-collusion rounds, paid its
+        /// tallied budget.
+        ///
+        /// Challenged campaigns are frozen out of matching until resolved, and rounds cannot be
+        /// finalized while one of their campaigns is challenged. Successful campaigns can be
+        /// settled before the round is finalized.
+        fn match_eligible(campaign_id: CampaignId) -> bool {
+            !Challenges::<T>::contains_key(campaign_id) &&
+                Campaigns::<T>::get(campaign_id).is_some_and(|campaign| {
+                    matches!(campaign.status, CampaignStatus::Success | CampaignStatus::Settled)
+                })
+        }
+
+        /// Split `pool` between the approved, successful or settled campaigns of a round.
+        ///
+        /// Each campaign is weighted by the square of the sum of the square roots of its
+        /// contributions, so many small contributors attract more matching than a few large ones.
+        /// Anti-collusion rounds take the sums from their tally instead.
+        /// No campaign receives more than `match_cap` of the pool; the excess stays in the pot.
+        pub fn quadratic_match(
+            round_id: RoundId,
+            pool: BalanceOf<T>,
+            match_cap: Perbill,
+        ) -> Vec<(CampaignId, BalanceOf<T>)> {
+            let weights: Vec<(CampaignId, BalanceOf<T>)> = match RoundTallies::<T>::get(round_id) {
+                Some(tally) => tally
+                    .into_iter()
+                    .filter(|entry| Self::match_eligible(entry.campaign_id))
+                    .map(|entry| (entry.campaign_id, entry.root_sum.saturating_mul(entry.root_sum)))
+                    .collect(),
+                None => Self::contribution_weights(round_id),
+            };
+            
+            let total = weights
+                .iter()
+                .fold(BalanceOf::<T>::zero(), |acc, (_, weight)| acc.saturating_add(*weight));
+            if total.is_zero() {
+                return Vec::new();
+            }
             
+            let cap = match_cap * pool;
+            weights
+                .into_iter()
+                .map(|(campaign_id, weight)| {
+                    (campaign_id, (Perbill::from_rational(weight, total) * pool).min(cap))
+                })
+                .collect()
+        }
+
+        /// Quadratic weights of the approved, successful or settled campaigns of a round.
+        ///
+        /// Every contribution to the campaign counts, including those made before the round
+        /// started. Campaigns end within their round, so none are made after it.
+        fn contribution_weights(round_id: RoundId) -> Vec<(CampaignId, BalanceOf<T>)> {
+            RoundCampaigns::<T>::get(round_id)
+                .into_iter()
+                .filter(|campaign_id| Self::match_eligible(*campaign_id))
+                .map(|campaign_id| {
+                    let root_sum = CampaignRootSums::<T>::get(campaign_id);
+                    (campaign_id, root_sum.saturating_mul(root_sum))
+                })
+                .collect()
+        }
+
+        /// Replace the square root of a contributor's `old` total with that of their `new` total
+        /// in the campaign's root sum.
+        fn note_root_sum(campaign_id: CampaignId, old: BalanceOf<T>, new: BalanceOf<T>) {
+            CampaignRootSums::<T>::mutate(campaign_id, |root_sum| {
+                *root_sum = root_sum
+                    .saturating_sub(old.integer_sqrt())
+                    .saturating_add(new.integer_sqrt());
+            });
+        }
+
+        /// Check the registry invariants:
+        ///
+        /// * `matched` of every campaign that is not being reaped equals its `CampaignRefunds` plus
+        ///   the sum of its `CampaignContributions`. `CampaignRootSums` of every campaign whose
+        ///   contributions have not been released is the sum of their square roots.
+        /// * `ActiveCampaigns` holds exactly the campaigns with `Active` or `Paused` status.
+        /// * Every account's reserved balance covers its outstanding contributions, campaign
+        ///   deposits, commitments and challenge bonds.
+        /// * No `Success` or `Failed` campaign ends in the future.
+        /// * `TierBackers` counts the `TierSelections` of each tier, within its supply.
+        /// * `CampaignsByOwner` indexes exactly the stored campaigns. Every campaign is listed
+        ///   under its category and tags unless it is in `DelistedCampaigns`, which only holds
+        ///   cancelled campaigns, settled or not, and none of those are listed.
+        /// * `CampaignRound`, `RoundCampaigns`, `RoundMatches` and pending or approved
+        ///   `RoundApplications` only refer to stored campaigns.
+        /// * The `RoundSignups` of each round add up to its coordinated budget.
+        #[cfg(any(feature = "try-runtime", test))]
+        pub fn do_try_state() -> Result<(), sp_runtime::TryRuntimeError> {
+            use alloc::collections::{BTreeMap, BTreeSet};
+
+            let now = T::Timestamp::now();
+            let active: BTreeSet<CampaignId> = ActiveCampaigns::<T>::get().into_iter().collect();
+            let mut reserved: BTreeMap<T::AccountId, BalanceOf<T>> = BTreeMap::new();
+            let mut owe = |who: &T::AccountId, amount: BalanceOf<T>| {
+                let total = reserved.entry(who.clone()).or_insert_with(Zero::zero);
+                *total = total.saturating_add(amount);
+            };
+
+            for (campaign_id, campaign) in Campaigns::<T>::iter() {
+                let status = campaign.status.clone();
+                ensure!(
+                    active.contains(&campaign_id) ==
+                        matches!(status, CampaignStatus::Active | CampaignStatus::Paused),
+                    "ActiveCampaigns out of sync with campaign status"
+                );
+                ensure!(
+                    !matches!(status, CampaignStatus::Success | CampaignStatus::Failed) ||
+                        now >= campaign.end,
+                    "campaign finalized before its end"
+                );
+
+                // Refunds remove entries and settlement releases them without removing them
+                let untouched = matches!(
+                    status,
+                    CampaignStatus::Upcoming |
+                        CampaignStatus::Active |
+                        CampaignStatus::Paused |
+                        CampaignStatus::Success
+                ) && !SettlementCursors::<T>::contains_key(campaign_id);
+                // Reaping removes entries in batches before it removes the campaign
+                let reaping = ReapCursors::<T>::contains_key(campaign_id);
+                let outstanding = status != CampaignStatus::Settled &&
+                    !SettlementCursors::<T>::contains_key(campaign_id);
+
+                let mut total = BalanceOf::<T>::zero();
+                let mut root_sum = BalanceOf::<T>::zero();
+                for (contributor, amount) in CampaignContributions::<T>::iter_prefix(campaign_id) {
+                    total = total.saturating_add(amount);
+                    root_sum = root_sum.saturating_add(amount.integer_sqrt());
+                    if outstanding {
+                        owe(&contributor, amount);
+                    }
+                }
+                ensure!(
+                    reaping ||
+                        total.saturating_add(CampaignRefunds::<T>::get(campaign_id)) == campaign.matched,
+                    "campaign matched differs from its refunds and contributions"
+                );
+                ensure!(
+                    !untouched || root_sum == CampaignRootSums::<T>::get(campaign_id),
+                    "campaign root sum differs from its contributions"
+                );
+                owe(&campaign.owner, campaign.deposit);
+
+                ensure!(
+                    CampaignsByOwner::<T>::contains_key(&campaign.owner, campaign_id),
+                    "campaign missing from CampaignsByOwner"
+                );
+                // Cancelled campaigns stay delisted after settlement
+                let in_category = CampaignsByCategory::<T>::contains_key(campaign.metadata.category, campaign_id);
+                let in_tags: Vec<bool> = campaign
+                    .metadata
+                    .tags
+                    .iter()
+                    .map(|tag| CampaignsByTag::<T>::contains_key(tag, campaign_id))
+                    .collect();
+                if DelistedCampaigns::<T>::contains_key(campaign_id) {
+                    ensure!(
+                        matches!(status, CampaignStatus::Cancelled | CampaignStatus::Settled),
+                        "delisted campaign is neither cancelled nor settled"
+                    );
+                    ensure!(
+                        !in_category && !in_tags.contains(&true),
+                        "delisted campaign still in the category or tag indices"
+                    );
+                } else {
+                    ensure!(
+                        in_category && !in_tags.contains(&false),
+                        "campaign missing from the category or tag indices"
+                    );
+                }
+
+                let mut backers: BTreeMap<TierId, u32> = BTreeMap::new();
+                for tier_id in TierSelections::<T>::iter_prefix_values(campaign_id) {
+                    *backers.entry(tier_id).or_default() += 1;
+                }
+                for (tier_id, tier) in CampaignTiers::<T>::get(campaign_id).iter().enumerate() {
+                    let tier_id = tier_id as TierId;
+                    let count = backers.get(&tier_id).copied().unwrap_or_default();
+                    ensure!(
+                        TierBackers::<T>::get(campaign_id, tier_id) == count,
+                        "TierBackers out of sync with tier selections"
+                    );
+                    ensure!(
+                        tier.supply.is_none_or(|supply| count <= supply),
+                        "reward tier oversold"
+                    );
+                }
+            }
+
+            ensure!(
+                CampaignsByOwner::<T>::iter_keys().count() == Campaigns::<T>::iter_keys().count(),
+                "CampaignsByOwner indexes unknown campaigns"
+            );
+            for campaign_id in DelistedCampaigns::<T>::iter_keys() {
+                ensure!(
+                    Campaigns::<T>::contains_key(campaign_id),
+                    "DelistedCampaigns references an unknown campaign"
+                );
+            }
+
+            for campaign_id in CampaignRound::<T>::iter_keys() {
+                ensure!(
+                    Campaigns::<T>::contains_key(campaign_id),
+                    "CampaignRound references an unknown campaign"
+                );
+            }
+            for (round_id, campaigns) in RoundCampaigns::<T>::iter() {
+                ensure!(
+                    campaigns.iter().all(|campaign_id| CampaignRound::<T>::get(campaign_id) == Some(round_id)),
+                    "RoundCampaigns references a campaign outside the round"
+                );
+            }
+            for (_, campaign_id) in RoundMatches::<T>::iter_keys() {
+                ensure!(
+                    Campaigns::<T>::contains_key(campaign_id),
+                    "RoundMatches references an unknown campaign"
+                );
+            }
+            for (_, campaign_id, status) in RoundApplications::<T>::iter() {
+                ensure!(
+                    matches!(status, ApplicationStatus::Rejected { .. }) ||
+                        Campaigns::<T>::contains_key(campaign_id),
+                    "RoundApplications references an unknown campaign"
+                );
+            }
+            for (round_id, signups) in RoundSignups::<T>::iter() {
+                let budget = signups
+                    .iter()
+                    .fold(BalanceOf::<T>::zero(), |total, (_, budget)| total.saturating_add(*budget));
+                ensure!(
+                    Coordinations::<T>::get(round_id).is_some_and(|coordination| coordination.budget == budget),
+                    "RoundSignups differ from the coordinated budget"
+                );
+            }
+
+            for (_, who, commitment) in Commitments::<T>::iter() {
+                owe(&who, commitment.bound);
+            }
+            for challenge in Challenges::<T>::iter_values() {
+                owe(&challenge.challenger, challenge.bond);
+            }
+            for (who, amount) in reserved {
+                ensure!(
+                    T::Currency::reserved_balance(&who) >= amount,
+                    "reserved balance does not cover contributions, deposits, commitments and bonds"
+                );
+            }
             Ok(())
         }
     }
-} 
\ No newline at end of file
+}
@@ -0,0 +1,61 @@
+//! Validation of the links campaigns point contributors to.
+//!
+//! Links must be absolute URIs with one of two schemes, written in lowercase:
+//!
+//! * `https://host[:port][/path][?query][#fragment]`, where the host is a DNS name or an IPv4
+//!   address. User info and IPv6 literals are not accepted.
+//! * `ipfs://cid[/path][?query][#fragment]`, where the CID is checked by [`crate::cid::is_valid`].
+//!
+//! The rest of the link may contain any printable ASCII character other than a space, so
+//! non-ASCII characters have to be percent-encoded.
+
+/// Why a link was rejected.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LinkError {
+    /// The link does not start with `https://` or `ipfs://`
+    UnsupportedScheme,
+    /// The link has a supported scheme but is not a well-formed URI
+    Malformed,
+}
+
+/// Check that `link` is a well-formed `https://` or `ipfs://` URI.
+pub fn validate(link: &[u8]) -> Result<(), LinkError> {
+    let (rest, is_valid_authority): (_, fn(&[u8]) -> bool) =
+        if let Some(rest) = link.strip_prefix(b"https://") {
+            (rest, is_valid_host_and_port)
+        } else if let Some(rest) = link.strip_prefix(b"ipfs://") {
+            (rest, crate::cid::is_valid)
+        } else {
+            return Err(LinkError::UnsupportedScheme);
+        };
+    let end = rest.iter().position(|c| matches!(c, b'/' | b'?' | b'#')).unwrap_or(rest.len());
+    let (authority, tail) = rest.split_at(end);
+    if is_valid_authority(authority) && tail.iter().all(|c| c.is_ascii_graphic()) {
+        Ok(())
+    } else {
+        Err(LinkError::Malformed)
+    }
+}
+
+fn is_valid_host_and_port(authority: &[u8]) -> bool {
+    let mut parts = authority.splitn(2, |c| *c == b':');
+    let host = parts.next().unwrap_or_default();
+    let port_is_valid = parts.next().is_none_or(|port| {
+        !port.is_empty() &&
+            port.len() <= 5 &&
+            port.iter().all(u8::is_ascii_digit) &&
+            port.iter().fold(0u32, |acc, c| acc * 10 + u32::from(c - b'0')) <= u32::from(u16::MAX)
+    });
+    port_is_valid && !host.is_empty() && host.split(|c| *c == b'.').all(is_valid_label)
+}
+
+fn is_valid_label(label: &[u8]) -> bool {
+    match (label.first(), label.last()) {
+        (Some(first), Some(last)) =>
+            label.len() <= 63 &&
+                *first != b'-' &&
+                *last != b'-' &&
+                label.iter().all(|c| c.is_ascii_alphanumeric() || *c == b'-'),
+        _ => false,
+    }
+}
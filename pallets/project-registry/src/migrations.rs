@@ -0,0 +1,214 @@
+//! Storage migrations for the project registry pallet.
+
+use super::*;
+use frame_support::{
+    migrations::{MigrationId, SteppedMigration, SteppedMigrationError},
+    weights::WeightMeter,
+};
+use core::marker::PhantomData;
+
+/// Identifier prefix of the pallet's multi-block migrations.
+const PALLET_MIGRATIONS_ID: &[u8; 23] = b"pallet-project-registry";
+
+/// Migration from the original, unversioned storage layout.
+pub mod v1 {
+    use super::*;
+
+    /// `Metadata` as stored before versioning.
+    #[derive(Encode, Decode)]
+    pub struct OldMetadata<T: Config> {
+        pub name: BoundedVec<u8, T::MaxNameLen>,
+        pub description: BoundedVec<u8, T::MaxDescLen>,
+        pub link: Option<BoundedVec<u8, T::MaxLinkLen>>,
+    }
+
+    /// `Campaign` as stored before versioning.
+    #[derive(Encode, Decode)]
+    pub struct OldCampaign<T: Config> {
+        pub owner: T::AccountId,
+        pub metadata: OldMetadata<T>,
+        pub start: MomentOf<T>,
+        pub end: MomentOf<T>,
+        pub soft_cap: BalanceOf<T>,
+        pub hard_cap: BalanceOf<T>,
+        pub matched: BalanceOf<T>,
+        pub status: CampaignStatus,
+    }
+
+    /// `Campaigns` with the original layout.
+    #[frame_support::storage_alias]
+    pub type Campaigns<T: Config> =
+        StorageMap<Pallet<T>, Blake2_128Concat, CampaignId, OldCampaign<T>>;
+
+    /// Progress of [`LazyMigrationV0ToV1`].
+    #[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, MaxEncodedLen)]
+    pub enum MigrationCursor<AccountId> {
+        /// Translating campaigns, after the given one
+        Campaigns(Option<CampaignId>),
+        /// Counting contributions, after the given one
+        Contributions(Option<(CampaignId, AccountId)>),
+    }
+
+    /// Moves the original layout to the current one, one campaign or contribution at a time.
+    ///
+    /// Campaigns get the deposit, no contributors yet, the all-or-nothing model, the
+    /// [`Category::Other`] category without tags and no content reference. Non-cancelled campaigns
+    /// are listed under their category, cancelled ones are marked as delisted. Campaigns that are
+    /// no longer active are dropped from `ActiveCampaigns`, where version 0 left cancelled ones.
+    /// Every contribution then counts towards its campaign's contributors and `CampaignRootSums`.
+    /// Version 0 refunds kept `matched`, so for failed and cancelled campaigns the difference
+    /// to the remaining contributions goes to `CampaignRefunds`.
+    ///
+    /// Version 0 reserved the compile-time `MinimumDeposit` for every campaign and released it on
+    /// cancellation, so `D` must carry that deposit. It stays reserved until the metadata is next
+    /// updated. `P` seeds `Parameters`.
+    pub struct LazyMigrationV0ToV1<T, P, D>(PhantomData<(T, P, D)>);
+
+    impl<T: Config, P: Get<ParametersOf<T>>, D: Get<BalanceOf<T>>> LazyMigrationV0ToV1<T, P, D> {
+        /// Translate the campaign after `last`, if any is left.
+        fn migrate_campaign(last: Option<CampaignId>) -> MigrationCursor<T::AccountId> {
+            let mut iter = match last {
+                Some(last) => Campaigns::<T>::iter_from(crate::Campaigns::<T>::hashed_key_for(last)),
+                None => Campaigns::<T>::iter(),
+            };
+            let Some((campaign_id, old)) = iter.next() else {
+                return MigrationCursor::Contributions(None);
+            };
+
+            let cancelled = old.status == CampaignStatus::Cancelled;
+            let campaign = Campaign {
+                owner: old.owner,
+                metadata: Metadata {
+                    name: old.metadata.name,
+                    description: old.metadata.description,
+                    link: old.metadata.link,
+                    category: Category::Other,
+                    tags: Default::default(),
+                    content: None,
+                },
+                start: old.start,
+                end: old.end,
+                soft_cap: old.soft_cap,
+                hard_cap: old.hard_cap,
+                matched: old.matched,
+                status: old.status,
+                deposit: if cancelled { Zero::zero() } else { D::get() },
+                contributors: 0,
+                funding_model: FundingModel::AllOrNothing,
+            };
+
+            CampaignsByOwner::<T>::insert(&campaign.owner, campaign_id, ());
+            if cancelled {
+                DelistedCampaigns::<T>::insert(campaign_id, ());
+            } else {
+                CampaignsByCategory::<T>::insert(Category::Other, campaign_id, ());
+            }
+            if campaign.status != CampaignStatus::Active {
+                ActiveCampaigns::<T>::mutate(|active| active.retain(|id| *id != campaign_id));
+            }
+            // Whatever is not left in contributions once they are counted was refunded
+            if matches!(campaign.status, CampaignStatus::Failed | CampaignStatus::Cancelled) {
+                CampaignRefunds::<T>::insert(campaign_id, campaign.matched);
+            }
+            crate::Campaigns::<T>::insert(campaign_id, campaign);
+
+            MigrationCursor::Campaigns(Some(campaign_id))
+        }
+
+        /// Count the contribution after `last`, or finish the migration once none is left.
+        fn migrate_contribution(
+            last: Option<(CampaignId, T::AccountId)>,
+        ) -> Option<MigrationCursor<T::AccountId>> {
+            let mut iter = match last {
+                Some((campaign_id, who)) => CampaignContributions::<T>::iter_from(
+                    CampaignContributions::<T>::hashed_key_for(campaign_id, who),
+                ),
+                None => CampaignContributions::<T>::iter(),
+            };
+            let Some((campaign_id, who, amount)) = iter.next() else {
+                StorageVersion::new(1).put::<Pallet<T>>();
+                return None;
+            };
+
+            crate::Campaigns::<T>::mutate(campaign_id, |campaign| {
+                if let Some(campaign) = campaign {
+                    campaign.contributors = campaign.contributors.saturating_add(1);
+                    if matches!(campaign.status, CampaignStatus::Failed | CampaignStatus::Cancelled) {
+                        CampaignRefunds::<T>::mutate(campaign_id, |refunded| {
+                            *refunded = refunded.saturating_sub(amount)
+                        });
+                    }
+                }
+            });
+            CampaignRootSums::<T>::mutate(campaign_id, |sum| *sum = sum.saturating_add(amount.integer_sqrt()));
+
+            Some(MigrationCursor::Contributions(Some((campaign_id, who))))
+        }
+    }
+
+    impl<T: Config, P: Get<ParametersOf<T>>, D: Get<BalanceOf<T>>> SteppedMigration
+        for LazyMigrationV0ToV1<T, P, D>
+    {
+        type Cursor = MigrationCursor<T::AccountId>;
+        type Identifier = MigrationId<23>;
+
+        fn id() -> Self::Identifier {
+            MigrationId { pallet_id: *PALLET_MIGRATIONS_ID, version_from: 0, version_to: 1 }
+        }
+
+        fn step(
+            mut cursor: Option<Self::Cursor>,
+            meter: &mut WeightMeter,
+        ) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+            if cursor.is_none() && Pallet::<T>::on_chain_storage_version() != 0 {
+                return Ok(None);
+            }
+
+            // The heavier of a campaign, plus `Parameters` on the first step, and a contribution
+            let required = T::DbWeight::get().reads_writes(4, 6);
+            if meter.remaining().any_lt(required) {
+                return Err(SteppedMigrationError::InsufficientWeight { required });
+            }
+
+            while meter.try_consume(required).is_ok() {
+                cursor = match cursor {
+                    None => {
+                        Parameters::<T>::put(P::get());
+                        Some(Self::migrate_campaign(None))
+                    },
+                    Some(MigrationCursor::Campaigns(last)) => Some(Self::migrate_campaign(last)),
+                    Some(MigrationCursor::Contributions(last)) => Self::migrate_contribution(last),
+                };
+                if cursor.is_none() {
+                    break;
+                }
+            }
+
+            Ok(cursor)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+            Ok((Campaigns::<T>::iter_keys().count() as u32).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+            let old_count = u32::decode(&mut &state[..])
+                .map_err(|_| "project-registry: invalid pre-upgrade state")?;
+            // `iter_values` skips entries that fail to decode with the new layout
+            let new_count = crate::Campaigns::<T>::iter_values().count() as u32;
+            ensure!(old_count == new_count, "project-registry: campaigns lost in migration");
+            let indexed = CampaignsByOwner::<T>::iter_keys().count() as u32;
+            ensure!(old_count == indexed, "project-registry: campaigns missing from CampaignsByOwner");
+            ensure!(Parameters::<T>::exists(), "project-registry: parameters not initialised");
+            for (campaign_id, campaign) in crate::Campaigns::<T>::iter() {
+                ensure!(
+                    campaign.contributors == CampaignContributions::<T>::iter_prefix(campaign_id).count() as u32,
+                    "project-registry: contributors not counted"
+                );
+            }
+            Ok(())
+        }
+    }
+}
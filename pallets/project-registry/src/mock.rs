@@ -1,14 +1,14 @@
 use crate as pallet_project_registry;
+use frame::deps::{frame_support, frame_system, sp_io, sp_runtime};
+use polkadot_sdk::{pallet_balances, pallet_timestamp};
 use frame_support::{
-    parameter_types,
-    traits::{ConstU16, ConstU32, ConstU64},
-};
-use frame_system as system;
-use sp_core::H256;
-use sp_runtime::{
-    traits::{BlakeTwo256, IdentityLookup},
-    BuildStorage,
+    derive_impl, parameter_types,
+    traits::ConstU64,
+    PalletId,
 };
+use frame_system::{EnsureRoot, EnsureRootWithSuccess};
+use std::cell::RefCell;
+use sp_runtime::{BuildStorage, Perbill, Permill};
 
 type Block = frame_system::mocking::MockBlock<Test>;
 
@@ -23,46 +23,19 @@ frame_support::construct_runtime!(
     }
 );
 
-impl system::Config for Test {
-    type BaseCallFilter = frame_support::traits::Everything;
-    type BlockWeights = ();
-    type BlockLength = ();
-    type DbWeight = ();
-    type RuntimeOrigin = RuntimeOrigin;
-    type RuntimeCall = RuntimeCall;
-    type Nonce = u64;
-    type Hash = H256;
-    type Hashing = BlakeTwo256;
-    type AccountId = u64;
-    type Lookup = IdentityLookup<Self::AccountId>;
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
     type Block = Block;
-    type RuntimeEvent = RuntimeEvent;
-    type BlockHashCount = ConstU64<250>;
-    type Version = ();
-    type PalletInfo = PalletInfo;
+    // Nonzero, so the migration's weight bound is exercised
+    type DbWeight = frame_support::weights::constants::RocksDbWeight;
     type AccountData = pallet_balances::AccountData<u64>;
-    type OnNewAccount = ();
-    type OnKilledAccount = ();
-    type SystemWeightInfo = ();
-    type SS58Prefix = ConstU16<42>;
-    type OnSetCode = ();
-    type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
 impl pallet_balances::Config for Test {
     type Balance = u64;
-    type DustRemoval = ();
-    type RuntimeEvent = RuntimeEvent;
     type ExistentialDeposit = ConstU64<1>;
     type AccountStore = System;
-    type WeightInfo = ();
-    type MaxLocks = ();
-    type MaxReserves = ();
-    type ReserveIdentifier = [u8; 8];
-    type RuntimeHoldReason = ();
-    type FreezeIdentifier = ();
-    type MaxHolds = ();
-    type MaxFreezes = ();
 }
 
 impl pallet_timestamp::Config for Test {
@@ -77,7 +50,25 @@ parameter_types! {
     pub const MaxDescLen: u32 = 1000;
     pub const MaxLinkLen: u32 = 200;
     pub const MaxActive: u32 = 100;
-    pub const MinimumDeposit: u64 = 100;
+    pub const RegistryPalletId: PalletId = PalletId(*b"py/prreg");
+    pub const ApplicationCutoff: u64 = 10;
+    pub const MaxRoundCampaigns: u32 = 10;
+    pub const TreasuryAccount: u64 = 99;
+    pub const RetentionPeriod: u64 = 1000;
+    pub const MaxReapBatch: u32 = 1;
+    pub const MaxMessageLen: u32 = 64;
+    pub const TallyTimeout: u64 = 500;
+    pub const MaxSignups: u32 = 3;
+    pub const MaxTiers: u32 = 3;
+    pub const MaxStretchGoals: u32 = 3;
+    pub const MaxExtension: u64 = 100;
+    pub const MaxPauseDuration: u64 = 300;
+    pub const MaxTags: u32 = 3;
+    pub static DepositBase: u64 = 100;
+    pub static DepositPerByte: u64 = 0;
+    pub const MaxRevisions: u32 = 2;
+    pub static PayoutDelay: u64 = 0;
+    pub const TreasurySpendPeriod: u64 = 1000;
 }
 
 impl pallet_project_registry::Config for Test {
@@ -88,13 +79,91 @@ impl pallet_project_registry::Config for Test {
     type MaxDescLen = MaxDescLen;
     type MaxLinkLen = MaxLinkLen;
     type MaxActive = MaxActive;
-    type MinimumDeposit = MinimumDeposit;
+    type ParameterOrigin = EnsureRoot<u64>;
+    type AdminOrigin = EnsureRoot<u64>;
+    type Slashed = ();
+    type OnSuccessFee = ();
+    type TreasuryAccount = TreasuryAccount;
+    type TreasurySpendOrigin = EnsureRootWithSuccess<u64, ConstU64<1000>>;
+    type TreasurySpendPeriod = TreasurySpendPeriod;
+    type RoundCuratorOrigin = EnsureRoot<u64>;
+    type PalletId = RegistryPalletId;
+    type ApplicationCutoff = ApplicationCutoff;
+    type MaxRoundCampaigns = MaxRoundCampaigns;
+    type RetentionPeriod = RetentionPeriod;
+    type MaxReapBatch = MaxReapBatch;
+    type TallyVerifier = crate::tally::InsecureHashVerifier;
+    type MaxMessageLen = MaxMessageLen;
+    type TallyTimeout = TallyTimeout;
+    type MaxSignups = MaxSignups;
+    type OnContribution = RecordContributions;
+    type MaxTiers = MaxTiers;
+    type MaxStretchGoals = MaxStretchGoals;
+    type MaxExtension = MaxExtension;
+    type MaxPauseDuration = MaxPauseDuration;
+    type MaxTags = MaxTags;
+    type DepositBase = DepositBase;
+    type DepositPerByte = DepositPerByte;
+    type MaxRevisions = MaxRevisions;
+    type MetadataValidator = ReservedNames;
+    type PayoutDelay = PayoutDelay;
+}
+
+// `(campaign_id, round_id, who, total)` of an `OnContribution` call.
+pub type ContributionCall = (u32, Option<u32>, u64, u64);
+
+thread_local! {
+    pub static CONTRIBUTIONS: RefCell<Vec<ContributionCall>> = const { RefCell::new(Vec::new()) };
+}
+
+// Records every `OnContribution` call.
+pub struct RecordContributions;
+
+impl pallet_project_registry::OnContribution<u64, u64> for RecordContributions {
+    fn on_contribution(campaign_id: u32, round_id: Option<u32>, who: &u64, total: u64) {
+        CONTRIBUTIONS.with(|calls| calls.borrow_mut().push((campaign_id, round_id, *who, total)));
+    }
+
+    fn weight() -> frame_support::weights::Weight {
+        frame_support::weights::Weight::zero()
+    }
+}
+
+// Rejects campaigns named after the registry itself.
+pub struct ReservedNames;
+
+impl pallet_project_registry::MetadataValidator<pallet_project_registry::Metadata<Test>> for ReservedNames {
+    fn validate(metadata: &pallet_project_registry::Metadata<Test>) -> sp_runtime::DispatchResult {
+        if &metadata.name[..] == b"Project Registry" {
+            return Err(sp_runtime::DispatchError::Other("reserved name"));
+        }
+        Ok(())
+    }
 }
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-    system::GenesisConfig::<Test>::default()
+    let mut t = frame_system::GenesisConfig::<Test>::default()
         .build_storage()
-        .unwrap()
-        .into()
-} 
\ No newline at end of file
+        .unwrap();
+    pallet_project_registry::GenesisConfig::<Test> {
+        min_contribution: 1,
+        match_cap: Perbill::one(),
+        challenge_bond: 50,
+        success_fee: Permill::zero(),
+        settlement_bounty: 5,
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+    let mut ext: sp_io::TestExternalities = t.into();
+    // Stamps the current storage version, as the runtime's genesis does
+    ext.execute_with(<ProjectRegistry as frame_support::traits::OnGenesis>::on_genesis);
+    ext
+}
+// Run `test` in fresh storage and check the pallet invariants afterwards.
+pub fn build_and_execute(test: impl FnOnce()) {
+    new_test_ext().execute_with(|| {
+        test();
+        ProjectRegistry::do_try_state().expect("registry invariants hold");
+    });
+}
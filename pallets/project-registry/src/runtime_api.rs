@@ -0,0 +1,30 @@
+//! Runtime API for querying the project registry.
+
+use crate::{CampaignId, Category, TagId, TierId};
+use codec::Codec;
+use alloc::vec::Vec;
+use frame::deps::sp_api;
+
+sp_api::decl_runtime_apis! {
+    pub trait ProjectRegistryApi<AccountId, Balance>
+    where
+        AccountId: Codec,
+        Balance: Codec,
+    {
+        /// Contributors of a campaign who selected a reward tier, with the tier and the amount
+        /// they contributed, for the owner to fulfil the rewards.
+        fn fulfilment_list(campaign_id: CampaignId) -> Vec<(AccountId, TierId, Balance)>;
+
+        /// Target and description of each stretch goal the campaign has reached, in order.
+        fn reached_stretch_goals(campaign_id: CampaignId) -> Vec<(Balance, Vec<u8>)>;
+
+        /// Campaigns listed under a category. Cancelled and reaped campaigns are not listed.
+        fn campaigns_by_category(category: Category) -> Vec<CampaignId>;
+
+        /// Campaigns listed under a tag. Cancelled and reaped campaigns are not listed.
+        fn campaigns_by_tag(tag: TagId) -> Vec<CampaignId>;
+
+        /// Campaigns created by an account that have not been reaped.
+        fn campaigns_by_owner(owner: AccountId) -> Vec<CampaignId>;
+    }
+}
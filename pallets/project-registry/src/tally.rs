@@ -0,0 +1,99 @@
+//! Verification of coordinator tallies for anti-collusion rounds.
+//!
+//! In an anti-collusion round contributors sign up with a budget and allocate it with messages
+//! encrypted to the round coordinator, in the style of MACI. Only the coordinator can decrypt the
+//! messages, and a contributor can override an earlier allocation with a later message, so no
+//! contributor can prove to a briber how they allocated. The coordinator posts the per-campaign
+//! result together with a proof that it follows from every signup and message published on chain.
+//!
+//! Besides each signup's budget, the pallet only keeps hash chains over the signups and messages.
+//! Checking the proof against
+//! them is left to a [`TallyVerifier`], so a zk-SNARK verifier (e.g. Groth16 over BN254) can be
+//! plugged in without touching storage.
+
+use crate::CampaignId;
+use frame::deps::{frame_support::pallet_prelude::*, sp_io};
+
+/// Tallied allocations to one campaign.
+#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct TallyEntry<Balance> {
+    pub campaign_id: CampaignId,
+    /// Sum of the budget allocated to the campaign
+    pub spent: Balance,
+    /// Sum of the square roots of the individual allocations, weighting the quadratic match
+    pub root_sum: Balance,
+}
+
+/// Public inputs a tally proof is checked against.
+#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct TallyInputs<PublicKey, Hash, Balance> {
+    /// Key the messages were encrypted to
+    pub coordinator_key: PublicKey,
+    /// Hash chain over every signup of the round
+    pub signup_chain: Hash,
+    pub signups: u32,
+    /// Hash chain over every message of the round
+    pub message_chain: Hash,
+    pub messages: u32,
+    /// Total budget of the signups
+    pub budget: Balance,
+}
+
+/// Checks that a coordinator's tally is the correct result of processing a round's messages.
+pub trait TallyVerifier<Hash, Balance> {
+    /// Coordinator public key the messages are encrypted to
+    type PublicKey: Parameter + MaxEncodedLen;
+    /// Proof submitted with the tally
+    type Proof: Parameter;
+
+    /// Whether any tally can be verified. Rounds cannot be coordinated otherwise, as their
+    /// matching pool and signup budgets could never be released.
+    const ENABLED: bool = true;
+
+    /// Whether `proof` shows that `tally` follows from the signups and messages in `inputs`.
+    fn verify(
+        inputs: &TallyInputs<Self::PublicKey, Hash, Balance>,
+        tally: &[TallyEntry<Balance>],
+        proof: &Self::Proof,
+    ) -> bool;
+}
+
+/// Rejects every tally, disabling anti-collusion rounds.
+impl<Hash, Balance> TallyVerifier<Hash, Balance> for () {
+    type PublicKey = ();
+    type Proof = ();
+
+    const ENABLED: bool = false;
+
+    fn verify(_: &TallyInputs<(), Hash, Balance>, _: &[TallyEntry<Balance>], _: &()) -> bool {
+        false
+    }
+}
+
+/// Accepts a tally whose proof is the `blake2_256` hash of its public inputs and entries.
+///
+/// Anyone can produce such a proof, so this verifier only suits tests and development chains.
+pub struct InsecureHashVerifier;
+
+impl InsecureHashVerifier {
+    /// The proof [`InsecureHashVerifier`] expects for `tally`.
+    pub fn prove<Hash: Encode, Balance: Encode>(
+        inputs: &TallyInputs<[u8; 32], Hash, Balance>,
+        tally: &[TallyEntry<Balance>],
+    ) -> [u8; 32] {
+        sp_io::hashing::blake2_256(&(inputs, tally).encode())
+    }
+}
+
+impl<Hash: Encode, Balance: Encode> TallyVerifier<Hash, Balance> for InsecureHashVerifier {
+    type PublicKey = [u8; 32];
+    type Proof = [u8; 32];
+
+    fn verify(
+        inputs: &TallyInputs<[u8; 32], Hash, Balance>,
+        tally: &[TallyEntry<Balance>],
+        proof: &[u8; 32],
+    ) -> bool {
+        Self::prove(inputs, tally) == *proof
+    }
+}
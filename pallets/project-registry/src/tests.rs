@@ -1,28 +1,37 @@
-use crate::{mock::*, Error, Event, CampaignStatus};
-use frame_support::{assert_noop, assert_ok, BoundedVec};
+use crate::{mock::*, Error, Event, CampaignStatus, Category, FundingModel};
+use frame::deps::{frame_support, frame_system, sp_core, sp_io, sp_runtime};
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{Currency, Get, Hooks, ReservableCurrency},
+    BoundedVec,
+};
+use proptest::prelude::*;
 use sp_runtime::traits::BadOrigin;
 
-fn make_bounded_string<const N: u32>(s: &str) -> BoundedVec<u8, ConstU32<N>> {
+fn make_bounded_string<S: Get<u32>>(s: &str) -> BoundedVec<u8, S> {
     BoundedVec::try_from(s.as_bytes().to_vec()).unwrap()
 }
 
 #[test]
 fn create_campaign_works() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         // Arrange
         let owner = 1;
         System::set_block_number(1);
         Timestamp::set_timestamp(100);
         let _ = Balances::deposit_creating(&owner, 1000);
 
-        let name = make_bounded_string::<50>("Test Campaign");
-        let desc = make_bounded_string::<1000>("Description");
-        let link = Some(make_bounded_string::<200>("https://example.com"));
+        let name = make_bounded_string("Test Campaign");
+        let desc = make_bounded_string("Description");
+        let link = Some(make_bounded_string("https://example.com"));
         
-        let metadata = pallet_project_registry::Metadata {
+        let metadata = crate::Metadata {
             name,
             description: desc,
             link,
+            category: Category::Other,
+            tags: Default::default(),
+            content: None,
         };
 
         // Act
@@ -33,10 +42,13 @@ fn create_campaign_works() {
             300, // end
             500, // soft_cap
             1000, // hard_cap
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
         ));
 
         // Assert
-        let campaign = ProjectRegistry::campaigns(0).unwrap();
+        let campaign = crate::Campaigns::<Test>::get(0).unwrap();
         assert_eq!(campaign.owner, owner);
         assert_eq!(campaign.status, CampaignStatus::Upcoming);
         assert_eq!(campaign.soft_cap, 500);
@@ -51,15 +63,18 @@ fn create_campaign_works() {
 
 #[test]
 fn create_campaign_validates_caps() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let owner = 1;
         let _ = Balances::deposit_creating(&owner, 1000);
         Timestamp::set_timestamp(100);
 
-        let metadata = pallet_project_registry::Metadata {
-            name: make_bounded_string::<50>("Test"),
-            description: make_bounded_string::<1000>("Desc"),
+        let metadata = crate::Metadata {
+            name: make_bounded_string("Test"),
+            description: make_bounded_string("Desc"),
             link: None,
+            category: Category::Other,
+            tags: Default::default(),
+            content: None,
         };
 
         assert_noop!(
@@ -70,15 +85,78 @@ fn create_campaign_validates_caps() {
                 300,
                 1000, // soft_cap > hard_cap
                 500,  // hard_cap
+                Default::default(),
+                Default::default(),
+                FundingModel::AllOrNothing,
             ),
             Error::<Test>::CapsInvalid
         );
     });
 }
 
+#[test]
+fn create_campaign_validates_metadata() {
+    build_and_execute(|| {
+        let owner = 1;
+        let _ = Balances::deposit_creating(&owner, 1000);
+        Timestamp::set_timestamp(100);
+
+        let create = |name: &[u8], link: Option<&str>| {
+            ProjectRegistry::create_campaign(
+                RuntimeOrigin::signed(owner),
+                crate::Metadata {
+                    name: BoundedVec::try_from(name.to_vec()).unwrap(),
+                    link: link.map(make_bounded_string),
+                    ..round_metadata()
+                },
+                200,
+                300,
+                100,
+                1000,
+                Default::default(),
+                Default::default(),
+                FundingModel::AllOrNothing,
+            )
+        };
+
+        assert_noop!(create(b"", None), Error::<Test>::EmptyName);
+        assert_noop!(create(b"  ", None), Error::<Test>::EmptyName);
+        assert_noop!(create(b"Caf\xe9", None), Error::<Test>::NameNotUtf8);
+        assert_noop!(create(b"Tab\tbed", None), Error::<Test>::NameHasControlCharacters);
+        assert_noop!(
+            create(b"Test", Some("http://example.com")),
+            Error::<Test>::UnsupportedLinkScheme
+        );
+        assert_noop!(
+            create(b"Test", Some("https://exa mple.com")),
+            Error::<Test>::MalformedLink
+        );
+        assert_noop!(create(b"Test", Some("ipfs://notacid")), Error::<Test>::MalformedLink);
+        // The mock runtime's `MetadataValidator` reserves the registry's own name
+        assert_noop!(
+            create(b"Project Registry", None),
+            sp_runtime::DispatchError::Other("reserved name")
+        );
+
+        assert_ok!(create("Café".as_bytes(), Some("https://example.com:8443/about?lang=en")));
+        assert_ok!(create(
+            b"Test",
+            Some("ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG/readme.md"),
+        ));
+        assert_noop!(
+            ProjectRegistry::update_metadata(
+                RuntimeOrigin::signed(owner),
+                0,
+                crate::Metadata { name: make_bounded_string(""), ..round_metadata() },
+            ),
+            Error::<Test>::EmptyName
+        );
+    });
+}
+
 #[test]
 fn contribute_works() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         // Arrange
         let owner = 1;
         let contributor = 2;
@@ -87,10 +165,13 @@ fn contribute_works() {
         let _ = Balances::deposit_creating(&owner, 1000);
         let _ = Balances::deposit_creating(&contributor, 1000);
 
-        let metadata = pallet_project_registry::Metadata {
-            name: make_bounded_string::<50>("Test"),
-            description: make_bounded_string::<1000>("Desc"),
+        let metadata = crate::Metadata {
+            name: make_bounded_string("Test"),
+            description: make_bounded_string("Desc"),
             link: None,
+            category: Category::Other,
+            tags: Default::default(),
+            content: None,
         };
 
         assert_ok!(ProjectRegistry::create_campaign(
@@ -100,6 +181,9 @@ fn contribute_works() {
             300, // end
             500, // soft_cap
             1000, // hard_cap
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
         ));
 
         // Act
@@ -107,10 +191,12 @@ fn contribute_works() {
             RuntimeOrigin::signed(contributor),
             0, // campaign_id
             200, // amount
+            None,
+            false,
         ));
 
         // Assert
-        let campaign = ProjectRegistry::campaigns(0).unwrap();
+        let campaign = crate::Campaigns::<Test>::get(0).unwrap();
         assert_eq!(campaign.matched, 200);
         
         System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::ContributionMade {
@@ -121,19 +207,99 @@ fn contribute_works() {
     });
 }
 
+#[test]
+fn partial_contribution_takes_remaining_headroom() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor) = (1, 2);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        let _ = Balances::deposit_creating(&contributor, 2000);
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            500,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 900, None, false));
+        assert_noop!(
+            ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 300, None, false),
+            Error::<Test>::HardCapExceeded
+        );
+
+        // Act
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 300, None, true));
+
+        // Assert
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().matched, 1000);
+        assert_eq!(Balances::reserved_balance(contributor), 1000);
+        assert_eq!(Balances::free_balance(contributor), 1000);
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::ContributionMade {
+            campaign_id: 0,
+            who: contributor,
+            amount: 100,
+        }));
+        assert_noop!(
+            ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 300, None, true),
+            Error::<Test>::HardCapExceeded
+        );
+    });
+}
+
+#[test]
+fn partial_contribution_respects_minimum() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor) = (1, 2);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        let _ = Balances::deposit_creating(&contributor, 2000);
+        crate::Parameters::<Test>::mutate(|parameters| parameters.min_contribution = 50);
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            500,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 980, None, false));
+
+        // Act & Assert
+        // Only 20 of the 300 fit under the hard cap
+        assert_noop!(
+            ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 300, None, true),
+            Error::<Test>::ContributionTooLow
+        );
+    });
+}
+
 #[test]
 fn cancel_campaign_works() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         // Arrange
         let owner = 1;
         System::set_block_number(1);
         Timestamp::set_timestamp(100);
         let _ = Balances::deposit_creating(&owner, 1000);
 
-        let metadata = pallet_project_registry::Metadata {
-            name: make_bounded_string::<50>("Test"),
-            description: make_bounded_string::<1000>("Desc"),
+        let metadata = crate::Metadata {
+            name: make_bounded_string("Test"),
+            description: make_bounded_string("Desc"),
             link: None,
+            category: Category::Other,
+            tags: Default::default(),
+            content: None,
         };
 
         assert_ok!(ProjectRegistry::create_campaign(
@@ -143,6 +309,9 @@ fn cancel_campaign_works() {
             300,
             500,
             1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
         ));
 
         // Act
@@ -152,7 +321,7 @@ fn cancel_campaign_works() {
         ));
 
         // Assert
-        let campaign = ProjectRegistry::campaigns(0).unwrap();
+        let campaign = crate::Campaigns::<Test>::get(0).unwrap();
         assert_eq!(campaign.status, CampaignStatus::Cancelled);
         
         System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::CampaignCancelled {
@@ -163,7 +332,7 @@ fn cancel_campaign_works() {
 
 #[test]
 fn claim_refund_works() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         // Arrange
         let owner = 1;
         let contributor = 2;
@@ -172,10 +341,13 @@ fn claim_refund_works() {
         let _ = Balances::deposit_creating(&owner, 1000);
         let _ = Balances::deposit_creating(&contributor, 1000);
 
-        let metadata = pallet_project_registry::Metadata {
-            name: make_bounded_string::<50>("Test"),
-            description: make_bounded_string::<1000>("Desc"),
+        let metadata = crate::Metadata {
+            name: make_bounded_string("Test"),
+            description: make_bounded_string("Desc"),
             link: None,
+            category: Category::Other,
+            tags: Default::default(),
+            content: None,
         };
 
         assert_ok!(ProjectRegistry::create_campaign(
@@ -185,12 +357,17 @@ fn claim_refund_works() {
             300,
             500,
             1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
         ));
 
         assert_ok!(ProjectRegistry::contribute(
             RuntimeOrigin::signed(contributor),
             0,
             200,
+            None,
+            false,
         ));
 
         assert_ok!(ProjectRegistry::cancel_campaign(
@@ -206,9 +383,11 @@ fn claim_refund_works() {
 
         // Assert
         assert_eq!(
-            ProjectRegistry::campaign_contributions(0, contributor),
+            crate::CampaignContributions::<Test>::get(0, contributor),
             0
         );
+        assert_eq!(crate::CampaignRefunds::<Test>::get(0), 200);
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().matched, 200);
         
         System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::RefundClaimed {
             campaign_id: 0,
@@ -220,17 +399,20 @@ fn claim_refund_works() {
 
 #[test]
 fn update_metadata_works() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         // Arrange
         let owner = 1;
         System::set_block_number(1);
         Timestamp::set_timestamp(100);
         let _ = Balances::deposit_creating(&owner, 1000);
 
-        let metadata = pallet_project_registry::Metadata {
-            name: make_bounded_string::<50>("Test"),
-            description: make_bounded_string::<1000>("Desc"),
+        let metadata = crate::Metadata {
+            name: make_bounded_string("Test"),
+            description: make_bounded_string("Desc"),
             link: None,
+            category: Category::Other,
+            tags: Default::default(),
+            content: None,
         };
 
         assert_ok!(ProjectRegistry::create_campaign(
@@ -240,12 +422,18 @@ fn update_metadata_works() {
             300,
             500,
             1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
         ));
 
-        let new_metadata = pallet_project_registry::Metadata {
-            name: make_bounded_string::<50>("Updated Test"),
-            description: make_bounded_string::<1000>("Updated Desc"),
-            link: Some(make_bounded_string::<200>("https://test.com")),
+        let new_metadata = crate::Metadata {
+            name: make_bounded_string("Updated Test"),
+            description: make_bounded_string("Updated Desc"),
+            link: Some(make_bounded_string("https://test.com")),
+            category: Category::Other,
+            tags: Default::default(),
+            content: None,
         };
 
         // Act
@@ -256,20 +444,90 @@ fn update_metadata_works() {
         ));
 
         // Assert
-        let campaign = ProjectRegistry::campaigns(0).unwrap();
+        let campaign = crate::Campaigns::<Test>::get(0).unwrap();
         assert_eq!(campaign.metadata.name, new_metadata.name);
         assert_eq!(campaign.metadata.description, new_metadata.description);
         assert_eq!(campaign.metadata.link, new_metadata.link);
         
         System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::MetadataUpdated {
             campaign_id: 0,
+            revision: 1,
+            old_hash: metadata_hash(&metadata),
+            new_hash: metadata_hash(&new_metadata),
+            reserved: 0,
+            unreserved: 0,
+        }));
+    });
+}
+
+fn metadata_hash(metadata: &crate::Metadata<Test>) -> sp_core::H256 {
+    use sp_runtime::traits::{BlakeTwo256, Hash};
+    BlakeTwo256::hash_of(metadata)
+}
+
+#[test]
+fn metadata_revisions_log_updates_and_restrict_active_edits() {
+    build_and_execute(|| {
+        // Arrange
+        let owner = 1;
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        let described = |description: &str| crate::Metadata {
+            description: make_bounded_string(description),
+            ..round_metadata()
+        };
+
+        // Act
+        assert_noop!(
+            ProjectRegistry::update_metadata(
+                RuntimeOrigin::signed(owner),
+                0,
+                crate::Metadata { name: make_bounded_string("Renamed"), ..round_metadata() },
+            ),
+            Error::<Test>::RestrictedEdit
+        );
+        System::set_block_number(2);
+        assert_ok!(ProjectRegistry::update_metadata(RuntimeOrigin::signed(owner), 0, described("Live")));
+        System::set_block_number(3);
+        assert_ok!(ProjectRegistry::update_metadata(RuntimeOrigin::signed(owner), 0, described("Funded")));
+
+        // Assert
+        // `MaxRevisions` is 2, so the revision logged on creation was dropped
+        let revisions: Vec<_> = crate::MetadataRevisions::<Test>::get(0)
+            .into_iter()
+            .map(|revision| (revision.number, revision.block, revision.hash))
+            .collect();
+        assert_eq!(
+            revisions,
+            vec![(1, 2, metadata_hash(&described("Live"))), (2, 3, metadata_hash(&described("Funded")))]
+        );
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::MetadataUpdated {
+            campaign_id: 0,
+            revision: 2,
+            old_hash: metadata_hash(&described("Live")),
+            new_hash: metadata_hash(&described("Funded")),
+            reserved: 0,
+            unreserved: 0,
         }));
     });
 }
 
 #[test]
 fn lifecycle_transitions_work() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         // Arrange
         let owner = 1;
         let contributor = 2;
@@ -278,10 +536,13 @@ fn lifecycle_transitions_work() {
         let _ = Balances::deposit_creating(&owner, 1000);
         let _ = Balances::deposit_creating(&contributor, 1000);
 
-        let metadata = pallet_project_registry::Metadata {
-            name: make_bounded_string::<50>("Test"),
-            description: make_bounded_string::<1000>("Desc"),
+        let metadata = crate::Metadata {
+            name: make_bounded_string("Test"),
+            description: make_bounded_string("Desc"),
             link: None,
+            category: Category::Other,
+            tags: Default::default(),
+            content: None,
         };
 
         assert_ok!(ProjectRegistry::create_campaign(
@@ -291,12 +552,17 @@ fn lifecycle_transitions_work() {
             150, // end soon
             500, // soft_cap
             1000, // hard_cap
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
         ));
 
         assert_ok!(ProjectRegistry::contribute(
             RuntimeOrigin::signed(contributor),
             0,
             600, // Above soft cap
+            None,
+            false,
         ));
 
         // Act - Move time past end
@@ -304,7 +570,7 @@ fn lifecycle_transitions_work() {
         ProjectRegistry::on_initialize(2);
 
         // Assert
-        let campaign = ProjectRegistry::campaigns(0).unwrap();
+        let campaign = crate::Campaigns::<Test>::get(0).unwrap();
         assert_eq!(campaign.status, CampaignStatus::Success);
         
         System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::CampaignFinalized {
@@ -312,4 +578,2846 @@ fn lifecycle_transitions_work() {
             status: CampaignStatus::Success,
         }));
     });
-} 
\ No newline at end of file
+}
+
+#[test]
+fn funding_model_decides_finalization_and_refunds() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor) = (1, 2);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        let _ = Balances::deposit_creating(&contributor, 2000);
+        let models = [
+            FundingModel::AllOrNothing,
+            FundingModel::KeepItAll,
+            FundingModel::Threshold(sp_runtime::Perbill::from_percent(50)),
+            FundingModel::Threshold(sp_runtime::Perbill::from_percent(80)),
+        ];
+        for (campaign_id, model) in models.into_iter().enumerate() {
+            assert_ok!(ProjectRegistry::create_campaign(
+                RuntimeOrigin::signed(owner),
+                round_metadata(),
+                50,
+                150,
+                500,
+                1000,
+                Default::default(),
+                Default::default(),
+                model,
+            ));
+            assert_ok!(ProjectRegistry::contribute(
+                RuntimeOrigin::signed(contributor),
+                campaign_id as u32,
+                300,
+                None,
+                false,
+            ));
+        }
+
+        // Act
+        Timestamp::set_timestamp(200);
+        ProjectRegistry::on_initialize(2);
+
+        // Assert
+        let statuses: Vec<_> = (0..4)
+            .map(|campaign_id| crate::Campaigns::<Test>::get(campaign_id).unwrap().status)
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                CampaignStatus::Failed,
+                CampaignStatus::Success,
+                CampaignStatus::Success,
+                CampaignStatus::Failed,
+            ]
+        );
+        assert_noop!(
+            ProjectRegistry::claim_refund(RuntimeOrigin::signed(contributor), 1),
+            Error::<Test>::NotRefundable
+        );
+        assert_ok!(ProjectRegistry::claim_refund(RuntimeOrigin::signed(contributor), 0));
+    });
+}
+
+#[test]
+fn threshold_campaign_without_contributions_fails() {
+    build_and_execute(|| {
+        // Arrange
+        let owner = 1;
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        let create = |model| {
+            ProjectRegistry::create_campaign(
+                RuntimeOrigin::signed(owner),
+                round_metadata(),
+                50,
+                150,
+                10,
+                1000,
+                Default::default(),
+                Default::default(),
+                model,
+            )
+        };
+        assert_noop!(
+            create(FundingModel::Threshold(sp_runtime::Perbill::zero())),
+            Error::<Test>::ZeroThreshold
+        );
+        // 1 ppb of a soft cap of 10 rounds down to nothing
+        assert_ok!(create(FundingModel::Threshold(sp_runtime::Perbill::from_parts(1))));
+
+        // Act
+        Timestamp::set_timestamp(200);
+        ProjectRegistry::on_initialize(2);
+
+        // Assert
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Failed);
+    });
+}
+
+#[test]
+fn extensions_push_end_out_up_to_max_extension() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor) = (1, 2);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        let _ = Balances::deposit_creating(&contributor, 1000);
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        let end = || crate::Campaigns::<Test>::get(0).unwrap().end;
+
+        // Act & Assert
+        assert_noop!(
+            ProjectRegistry::extend_campaign(RuntimeOrigin::signed(contributor), 0, 350),
+            Error::<Test>::NotOwner
+        );
+        assert_noop!(
+            ProjectRegistry::extend_campaign(RuntimeOrigin::signed(owner), 0, 450),
+            Error::<Test>::ExtensionTooLong
+        );
+        assert_ok!(ProjectRegistry::extend_campaign(RuntimeOrigin::signed(owner), 0, 350));
+        Timestamp::set_timestamp(320);
+        ProjectRegistry::on_initialize(2);
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Active);
+
+        // Contributions in the last 30 ms push the end out, within the remaining allowance
+        assert_ok!(ProjectRegistry::set_auto_extension(RuntimeOrigin::signed(owner), 0, Some(30)));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 50, None, false));
+        assert_eq!(end(), 350);
+        Timestamp::set_timestamp(340);
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 50, None, false));
+        assert_eq!(end(), 370);
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::CampaignExtended {
+            campaign_id: 0,
+            end: 370,
+        }));
+        assert_noop!(
+            ProjectRegistry::extend_campaign(RuntimeOrigin::signed(owner), 0, 401),
+            Error::<Test>::ExtensionTooLong
+        );
+
+        // Ended campaigns take no contributions and cannot be extended, even before they are
+        // finalized
+        Timestamp::set_timestamp(390);
+        assert_noop!(
+            ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 50, None, false),
+            Error::<Test>::CampaignEnded
+        );
+        assert_eq!(end(), 370);
+        assert_eq!(crate::CampaignExtensions::<Test>::get(0), 70);
+        assert_noop!(
+            ProjectRegistry::extend_campaign(RuntimeOrigin::signed(owner), 0, 400),
+            Error::<Test>::NotActive
+        );
+        ProjectRegistry::on_initialize(3);
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Success);
+    });
+}
+
+#[test]
+fn paused_campaign_rejects_contributions_and_defers_finalization() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor) = (1, 2);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        let _ = Balances::deposit_creating(&contributor, 1000);
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+
+        // Act
+        assert_noop!(
+            ProjectRegistry::pause_campaign(RuntimeOrigin::signed(contributor), 0),
+            Error::<Test>::NotOwner
+        );
+        assert_ok!(ProjectRegistry::pause_campaign(RuntimeOrigin::signed(owner), 0));
+
+        // Assert
+        assert_noop!(
+            ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 100, None, false),
+            Error::<Test>::CampaignPaused
+        );
+        Timestamp::set_timestamp(350);
+        ProjectRegistry::on_initialize(2);
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Paused);
+
+        // Resuming pushes the end out by the 250 ms the campaign was paused, within `MaxExtension`
+        assert_ok!(ProjectRegistry::resume_campaign(RuntimeOrigin::signed(owner), 0, true));
+        let campaign = crate::Campaigns::<Test>::get(0).unwrap();
+        assert_eq!(campaign.status, CampaignStatus::Active);
+        assert_eq!(campaign.end, 400);
+        assert_eq!(crate::CampaignExtensions::<Test>::get(0), 100);
+        assert_noop!(
+            ProjectRegistry::resume_campaign(RuntimeOrigin::signed(owner), 0, true),
+            Error::<Test>::NotPaused
+        );
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 100, None, false));
+        Timestamp::set_timestamp(550);
+        ProjectRegistry::on_initialize(3);
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Success);
+    });
+}
+
+#[test]
+fn paused_campaign_resumes_after_max_pause_duration() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor) = (1, 2);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        let _ = Balances::deposit_creating(&contributor, 1000);
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 100, None, false));
+        assert_ok!(ProjectRegistry::pause_campaign(RuntimeOrigin::signed(owner), 0));
+        Timestamp::set_timestamp(399);
+        ProjectRegistry::on_initialize(2);
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Paused);
+
+        // Act
+        Timestamp::set_timestamp(400);
+        ProjectRegistry::on_initialize(3);
+
+        // Assert
+        // The pause outlasted `MaxExtension`, so the campaign is finalized as soon as it resumes
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Success);
+        assert!(crate::PausedAt::<Test>::get(0).is_none());
+        assert!(crate::ActiveCampaigns::<Test>::get().is_empty());
+        assert_eq!(crate::CampaignExtensions::<Test>::get(0), 100);
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::CampaignResumed {
+            campaign_id: 0,
+            end: 400,
+        }));
+    });
+}
+
+#[test]
+fn automatic_resume_extends_end_by_paused_duration() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor) = (1, 2);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        let _ = Balances::deposit_creating(&contributor, 1000);
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            350,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::pause_campaign(RuntimeOrigin::signed(owner), 0));
+
+        // Act
+        Timestamp::set_timestamp(400);
+        ProjectRegistry::on_initialize(2);
+
+        // Assert: the 300 paused are made up for, up to `MaxExtension`
+        let campaign = crate::Campaigns::<Test>::get(0).unwrap();
+        assert_eq!((campaign.status, campaign.end), (CampaignStatus::Active, 450));
+        assert_eq!(crate::CampaignExtensions::<Test>::get(0), 100);
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::CampaignResumed {
+            campaign_id: 0,
+            end: 450,
+        }));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 100, None, false));
+    });
+}
+
+fn old_metadata() -> crate::migrations::v1::OldMetadata<Test> {
+    crate::migrations::v1::OldMetadata {
+        name: make_bounded_string("Round Campaign"),
+        description: make_bounded_string("Desc"),
+        link: None,
+    }
+}
+
+fn round_metadata() -> crate::Metadata<Test> {
+    crate::Metadata {
+        name: make_bounded_string("Round Campaign"),
+        description: make_bounded_string("Desc"),
+        link: None,
+        category: Category::Other,
+        tags: Default::default(),
+        content: None,
+    }
+}
+
+#[test]
+fn only_approved_campaigns_are_matched() {
+    build_and_execute(|| {
+        // Arrange
+        let (approved_owner, rejected_owner, contributor, sponsor) = (1, 3, 2, 4);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [approved_owner, rejected_owner, contributor, sponsor] {
+            let _ = Balances::deposit_creating(&who, 2000);
+        }
+
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 1000, None));
+        assert_ok!(ProjectRegistry::fund_round(RuntimeOrigin::signed(sponsor), 0, 1000));
+
+        for owner in [approved_owner, rejected_owner] {
+            assert_ok!(ProjectRegistry::create_campaign(
+                RuntimeOrigin::signed(owner),
+                round_metadata(),
+                50,
+                300,
+                100,
+                1000,
+                Default::default(),
+                Default::default(),
+                FundingModel::AllOrNothing,
+            ));
+        }
+        assert_ok!(ProjectRegistry::apply_to_round(RuntimeOrigin::signed(approved_owner), 0, 0));
+        assert_ok!(ProjectRegistry::apply_to_round(RuntimeOrigin::signed(rejected_owner), 0, 1));
+
+        let reason = sp_core::H256::repeat_byte(1);
+        assert_ok!(ProjectRegistry::approve_application(RuntimeOrigin::root(), 0, 0));
+        assert_ok!(ProjectRegistry::reject_application(RuntimeOrigin::root(), 0, 1, reason));
+        assert_noop!(
+            ProjectRegistry::approve_application(RuntimeOrigin::signed(approved_owner), 0, 1),
+            Error::<Test>::NotCurator
+        );
+
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 400, None, false));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 1, 400, None, false));
+
+        Timestamp::set_timestamp(1000);
+        ProjectRegistry::on_initialize(2);
+
+        // Act
+        assert_ok!(ProjectRegistry::finalize_round(RuntimeOrigin::signed(contributor), 0));
+
+        // Assert
+        assert_eq!(crate::RoundMatches::<Test>::get(0, 0), 1000);
+        assert_eq!(crate::RoundMatches::<Test>::get(0, 1), 0);
+        assert_eq!(Balances::free_balance(approved_owner), 2000 - 100 + 1000);
+        assert_eq!(
+            crate::RoundApplications::<Test>::get(0, 1),
+            Some(crate::ApplicationStatus::Rejected { reason })
+        );
+
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::MatchAllocated {
+            round_id: 0,
+            campaign_id: 0,
+            amount: 1000,
+        }));
+    });
+}
+
+#[test]
+fn applications_close_before_round_start() {
+    build_and_execute(|| {
+        let owner = 1;
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 1000, None));
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+
+        // ApplicationCutoff is 10, so applications close at 190
+        Timestamp::set_timestamp(190);
+        assert_noop!(
+            ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 0, 0),
+            Error::<Test>::ApplicationsClosed
+        );
+    });
+}
+
+#[test]
+fn set_parameters_updates_minimum_contribution() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor) = (1, 2);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        let _ = Balances::deposit_creating(&contributor, 1000);
+
+        let parameters = crate::RegistryParameters {
+            min_contribution: 50,
+            match_cap: sp_runtime::Perbill::from_percent(50),
+            challenge_bond: 50,
+            success_fee: sp_runtime::Permill::zero(),
+            settlement_bounty: 5,
+        };
+        assert_noop!(
+            ProjectRegistry::set_parameters(RuntimeOrigin::signed(owner), parameters.clone()),
+            BadOrigin
+        );
+
+        // Act
+        assert_ok!(ProjectRegistry::set_parameters(RuntimeOrigin::root(), parameters.clone()));
+
+        // Assert
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_noop!(
+            ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 49, None, false),
+            Error::<Test>::ContributionTooLow
+        );
+
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::ParametersUpdated {
+            parameters,
+        }));
+    });
+}
+
+#[test]
+fn admin_origin_cancels_and_appoints_curators() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, stranger, curator) = (1, 2, 3);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_noop!(
+            ProjectRegistry::create_round(RuntimeOrigin::signed(owner), 200, 1000, None),
+            BadOrigin
+        );
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 1000, None));
+        assert_ok!(ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 0, 0));
+
+        // Act
+        assert_noop!(
+            ProjectRegistry::set_round_curator(RuntimeOrigin::signed(curator), 0, Some(curator)),
+            BadOrigin
+        );
+        assert_ok!(ProjectRegistry::set_round_curator(RuntimeOrigin::root(), 0, Some(curator)));
+        assert_ok!(ProjectRegistry::approve_application(RuntimeOrigin::signed(curator), 0, 0));
+
+        assert_noop!(
+            ProjectRegistry::cancel_campaign(RuntimeOrigin::signed(stranger), 0),
+            Error::<Test>::NotOwner
+        );
+        assert_ok!(ProjectRegistry::cancel_campaign(RuntimeOrigin::root(), 0));
+
+        // Assert
+        assert_eq!(
+            crate::RoundApplications::<Test>::get(0, 0),
+            Some(crate::ApplicationStatus::Approved)
+        );
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Cancelled);
+        assert_eq!(Balances::reserved_balance(owner), 0);
+    });
+}
+
+#[test]
+fn force_cancel_slashes_deposit_and_enables_refunds() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor) = (1, 2);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        let _ = Balances::deposit_creating(&contributor, 1000);
+
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            150,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 300, None, false));
+        Timestamp::set_timestamp(200);
+        ProjectRegistry::on_initialize(2);
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Success);
+
+        let reason = sp_core::H256::repeat_byte(7);
+        assert_noop!(
+            ProjectRegistry::force_cancel_campaign(RuntimeOrigin::signed(owner), 0, reason, true),
+            BadOrigin
+        );
+
+        // Act
+        assert_ok!(ProjectRegistry::force_cancel_campaign(RuntimeOrigin::root(), 0, reason, true));
+
+        // Assert
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Cancelled);
+        assert_eq!(Balances::reserved_balance(owner), 0);
+        assert_eq!(Balances::free_balance(owner), 900);
+        assert_ok!(ProjectRegistry::claim_refund(RuntimeOrigin::signed(contributor), 0));
+        assert_eq!(Balances::free_balance(contributor), 1000);
+
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::CampaignForceCancelled {
+            campaign_id: 0,
+            reason,
+            slashed: 100,
+        }));
+    });
+}
+
+#[test]
+fn contributions_notify_on_contribution_hook() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor) = (1, 2);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        let _ = Balances::deposit_creating(&contributor, 1000);
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+
+        // Act
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 100, None, false));
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 1000, None));
+        assert_ok!(ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 0, 0));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 50, None, false));
+        assert_noop!(
+            ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 5000, None, false),
+            Error::<Test>::HardCapExceeded
+        );
+
+        // Assert
+        assert_eq!(
+            CONTRIBUTIONS.with(|calls| calls.borrow().clone()),
+            vec![(0, None, contributor, 100), (0, Some(0), contributor, 150)]
+        );
+    });
+}
+
+fn tier(min_amount: u64, supply: Option<u32>) -> crate::RewardTier<Test> {
+    crate::RewardTier { min_amount, description: make_bounded_string("Reward"), supply }
+}
+
+#[test]
+fn reward_tiers_enforce_minimum_and_supply() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, alice, bob) = (1, 2, 3);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [owner, alice, bob] {
+            let _ = Balances::deposit_creating(&who, 1000);
+        }
+        assert_noop!(
+            ProjectRegistry::create_campaign(
+                RuntimeOrigin::signed(owner),
+                round_metadata(),
+                50,
+                300,
+                100,
+                1000,
+                vec![tier(100, Some(0))].try_into().unwrap(),
+                Default::default(),
+                FundingModel::AllOrNothing,
+            ),
+            Error::<Test>::InvalidTier
+        );
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            vec![tier(100, Some(1)), tier(50, None)].try_into().unwrap(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+
+        // Act
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(alice), 0, 100, Some(0), false));
+        assert_noop!(
+            ProjectRegistry::contribute(RuntimeOrigin::signed(bob), 0, 100, Some(0), false),
+            Error::<Test>::TierSoldOut
+        );
+        assert_noop!(
+            ProjectRegistry::contribute(RuntimeOrigin::signed(bob), 0, 40, Some(1), false),
+            Error::<Test>::BelowTierMinimum
+        );
+        assert_noop!(
+            ProjectRegistry::contribute(RuntimeOrigin::signed(bob), 0, 60, Some(2), false),
+            Error::<Test>::TierNotFound
+        );
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(bob), 0, 60, Some(1), false));
+        // Switching tiers frees the place in the old one
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(alice), 0, 10, Some(1), false));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(bob), 0, 40, Some(0), false));
+
+        // Assert
+        assert_eq!(crate::TierBackers::<Test>::get(0, 0), 1);
+        assert_eq!(crate::TierBackers::<Test>::get(0, 1), 1);
+        let mut list = ProjectRegistry::fulfilment_list(0);
+        list.sort();
+        assert_eq!(list, vec![(alice, 1, 110), (bob, 0, 100)]);
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::TierSelected {
+            campaign_id: 0,
+            who: bob,
+            tier_id: 0,
+        }));
+
+        // A refund gives up the reward
+        assert_ok!(ProjectRegistry::cancel_campaign(RuntimeOrigin::signed(owner), 0));
+        assert_ok!(ProjectRegistry::claim_refund(RuntimeOrigin::signed(bob), 0));
+        assert_eq!(crate::TierBackers::<Test>::get(0, 0), 0);
+        assert_eq!(ProjectRegistry::fulfilment_list(0), vec![(alice, 1, 110)]);
+    });
+}
+
+fn stretch_goals(targets: &[u64]) -> crate::StretchGoalsOf<Test> {
+    targets
+        .iter()
+        .map(|&target| crate::StretchGoal { target, description: make_bounded_string("Goal") })
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap()
+}
+
+#[test]
+fn stretch_goals_unlock_headroom_beyond_hard_cap() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor) = (1, 2);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        let _ = Balances::deposit_creating(&contributor, 1000);
+        assert_noop!(
+            ProjectRegistry::create_campaign(
+                RuntimeOrigin::signed(owner),
+                round_metadata(),
+                50,
+                300,
+                100,
+                300,
+                Default::default(),
+                stretch_goals(&[400]),
+                FundingModel::AllOrNothing,
+            ),
+            Error::<Test>::InvalidStretchGoals
+        );
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            300,
+            Default::default(),
+            stretch_goals(&[200, 500, 800]),
+            FundingModel::AllOrNothing,
+        ));
+
+        // Act & Assert
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 150, None, false));
+        assert_noop!(
+            ProjectRegistry::unlock_stretch_goal(RuntimeOrigin::signed(owner), 0),
+            Error::<Test>::StretchGoalNotReached
+        );
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 60, None, false));
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::StretchGoalReached {
+            campaign_id: 0,
+            index: 0,
+            target: 200,
+        }));
+        assert_noop!(
+            ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 100, None, false),
+            Error::<Test>::HardCapExceeded
+        );
+        assert_noop!(
+            ProjectRegistry::unlock_stretch_goal(RuntimeOrigin::signed(contributor), 0),
+            Error::<Test>::NotOwner
+        );
+
+        assert_ok!(ProjectRegistry::unlock_stretch_goal(RuntimeOrigin::signed(owner), 0));
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().hard_cap, 500);
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 290, None, false));
+        assert_ok!(ProjectRegistry::unlock_stretch_goal(RuntimeOrigin::signed(owner), 0));
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().hard_cap, 800);
+        assert_noop!(
+            ProjectRegistry::unlock_stretch_goal(RuntimeOrigin::signed(owner), 0),
+            Error::<Test>::NoStretchGoal
+        );
+
+        let reached: Vec<u64> =
+            ProjectRegistry::reached_stretch_goals(0).into_iter().map(|goal| goal.target).collect();
+        assert_eq!(reached, vec![200, 500]);
+    });
+}
+
+#[test]
+fn cancel_campaign_removes_active_campaign() {
+    build_and_execute(|| {
+        let owner = 1;
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_eq!(crate::ActiveCampaigns::<Test>::get().into_inner(), vec![0]);
+
+        assert_ok!(ProjectRegistry::cancel_campaign(RuntimeOrigin::signed(owner), 0));
+
+        assert!(crate::ActiveCampaigns::<Test>::get().is_empty());
+    });
+}
+
+fn content(cid: &str, mime_type: &str) -> Option<crate::ContentRef<Test>> {
+    Some(crate::ContentRef {
+        cid: make_bounded_string(cid),
+        hash: sp_core::H256::repeat_byte(1),
+        mime_type: make_bounded_string(mime_type),
+    })
+}
+
+#[test]
+fn content_addressed_metadata_is_validated() {
+    build_and_execute(|| {
+        // Arrange
+        let owner = 1;
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        let create = |content_ref| {
+            ProjectRegistry::create_campaign(
+                RuntimeOrigin::signed(owner),
+                crate::Metadata { content: content_ref, ..round_metadata() },
+                50,
+                300,
+                100,
+                1000,
+                Default::default(),
+                Default::default(),
+                FundingModel::AllOrNothing,
+            )
+        };
+
+        assert_noop!(
+            create(content("bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzd", "text/markdown")),
+            Error::<Test>::InvalidCid
+        );
+        assert_noop!(
+            create(content("QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPb0G", "text/markdown")),
+            Error::<Test>::InvalidCid
+        );
+        assert_noop!(
+            create(content("QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG", "markdown")),
+            Error::<Test>::InvalidMimeType
+        );
+
+        // Act
+        assert_ok!(create(content("QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG", "text/markdown")));
+        assert_ok!(create(content(
+            "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+            "text/markdown",
+        )));
+
+        // Assert
+        let cid = crate::Campaigns::<Test>::get(1).unwrap().metadata.content.unwrap().cid;
+        assert_eq!(&cid[..1], b"b");
+    });
+}
+
+#[test]
+fn metadata_deposit_follows_encoded_size() {
+    build_and_execute(|| {
+        // Arrange
+        let owner = 1;
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        DepositBase::set(10);
+        DepositPerByte::set(2);
+
+        // 24 bytes encoded: 15 of name, 5 of description and one each for the link, category,
+        // tags and content
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            200,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().deposit, 10 + 2 * 24);
+        assert_eq!(Balances::reserved_balance(owner), 58);
+
+        // Act
+        // The content reference takes 1 + 47 + 32 + 14 bytes instead of 1
+        let with_content = crate::Metadata {
+            content: content("QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG", "text/markdown"),
+            ..round_metadata()
+        };
+        assert_ok!(ProjectRegistry::update_metadata(RuntimeOrigin::signed(owner), 0, with_content));
+        let grown = Balances::reserved_balance(owner);
+        assert_ok!(ProjectRegistry::update_metadata(
+            RuntimeOrigin::signed(owner),
+            0,
+            crate::Metadata { description: make_bounded_string(""), ..round_metadata() },
+        ));
+
+        // Assert
+        assert_eq!(grown, 58 + 2 * 93);
+        let revisions = crate::MetadataRevisions::<Test>::get(0);
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::MetadataUpdated {
+            campaign_id: 0,
+            revision: 1,
+            old_hash: metadata_hash(&round_metadata()),
+            new_hash: revisions[0].hash,
+            reserved: 186,
+            unreserved: 0,
+        }));
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::MetadataUpdated {
+            campaign_id: 0,
+            revision: 2,
+            old_hash: revisions[0].hash,
+            new_hash: revisions[1].hash,
+            reserved: 0,
+            unreserved: 186 + 8,
+        }));
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().deposit, 50);
+        assert_eq!(Balances::reserved_balance(owner), 50);
+    });
+}
+
+#[test]
+fn metadata_update_rebases_deposit_on_current_rates() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor) = (1, 2);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        let _ = Balances::deposit_creating(&contributor, 1000);
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            200,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_eq!(Balances::reserved_balance(owner), 100);
+        DepositBase::set(10);
+        DepositPerByte::set(2);
+
+        // Act
+        assert_ok!(ProjectRegistry::update_metadata(
+            RuntimeOrigin::signed(owner),
+            0,
+            crate::Metadata { description: make_bounded_string(""), ..round_metadata() },
+        ));
+
+        // Assert
+        // Whatever was reserved at the old rates is replaced by the deposit at the current ones
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::MetadataUpdated {
+            campaign_id: 0,
+            revision: 1,
+            old_hash: metadata_hash(&round_metadata()),
+            new_hash: crate::MetadataRevisions::<Test>::get(0)[1].hash,
+            reserved: 0,
+            unreserved: 50,
+        }));
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().deposit, 50);
+        assert_eq!(Balances::reserved_balance(owner), 50);
+    });
+}
+
+fn tagged_metadata(category: Category, tags: &[crate::TagId]) -> crate::Metadata<Test> {
+    crate::Metadata {
+        category,
+        tags: BoundedVec::try_from(tags.to_vec()).unwrap(),
+        ..round_metadata()
+    }
+}
+
+#[test]
+fn discovery_indices_follow_create_update_and_cancel() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, other) = (1, 2);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        let _ = Balances::deposit_creating(&other, 1000);
+
+        assert_noop!(
+            ProjectRegistry::create_campaign(
+                RuntimeOrigin::signed(owner),
+                tagged_metadata(Category::Tooling, &[7, 7]),
+                200,
+                300,
+                100,
+                1000,
+                Default::default(),
+                Default::default(),
+                FundingModel::AllOrNothing,
+            ),
+            Error::<Test>::DuplicateTag
+        );
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            tagged_metadata(Category::Tooling, &[7, 8]),
+            200,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(other),
+            tagged_metadata(Category::Tooling, &[8]),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        let sorted = |mut ids: Vec<crate::CampaignId>| {
+            ids.sort();
+            ids
+        };
+        assert_eq!(sorted(ProjectRegistry::campaigns_by_category(Category::Tooling)), vec![0, 1]);
+        assert_eq!(sorted(ProjectRegistry::campaigns_by_tag(8)), vec![0, 1]);
+        assert_eq!(ProjectRegistry::campaigns_by_owner(&owner), vec![0]);
+
+        // Act
+        assert_ok!(ProjectRegistry::update_metadata(
+            RuntimeOrigin::signed(owner),
+            0,
+            tagged_metadata(Category::Research, &[9]),
+        ));
+        assert_ok!(ProjectRegistry::cancel_campaign(RuntimeOrigin::signed(other), 1));
+
+        // Assert
+        assert!(ProjectRegistry::campaigns_by_category(Category::Tooling).is_empty());
+        assert_eq!(ProjectRegistry::campaigns_by_category(Category::Research), vec![0]);
+        assert!(ProjectRegistry::campaigns_by_tag(7).is_empty());
+        assert!(ProjectRegistry::campaigns_by_tag(8).is_empty());
+        assert_eq!(ProjectRegistry::campaigns_by_tag(9), vec![0]);
+        // Cancelled campaigns stay with their owner until they are reaped
+        assert_eq!(ProjectRegistry::campaigns_by_owner(&other), vec![1]);
+    });
+}
+
+#[test]
+fn cancelled_campaign_stays_delisted_once_settled() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, alice, bob) = (1, 2, 3);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [owner, alice, bob] {
+            let _ = Balances::deposit_creating(&who, 1000);
+        }
+
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            tagged_metadata(Category::Tooling, &[7]),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(alice), 0, 100, None, false));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(bob), 0, 100, None, false));
+        assert_ok!(ProjectRegistry::cancel_campaign(RuntimeOrigin::signed(owner), 0));
+        assert_ok!(ProjectRegistry::claim_refund(RuntimeOrigin::signed(alice), 0));
+
+        // Act
+        while crate::Campaigns::<Test>::get(0).unwrap().status != CampaignStatus::Settled {
+            assert_ok!(ProjectRegistry::settle_batch(RuntimeOrigin::signed(owner), 0, 1));
+        }
+
+        // Assert
+        assert!(crate::DelistedCampaigns::<Test>::contains_key(0));
+        assert!(ProjectRegistry::campaigns_by_category(Category::Tooling).is_empty());
+        assert!(ProjectRegistry::campaigns_by_tag(7).is_empty());
+        assert_eq!(Balances::free_balance(bob), 1000);
+        assert_ok!(ProjectRegistry::do_try_state());
+    });
+}
+
+#[test]
+fn upheld_challenge_rewards_challenger_and_cancels() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, challenger) = (1, 2);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        let _ = Balances::deposit_creating(&challenger, 1000);
+
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        let evidence = sp_core::H256::repeat_byte(3);
+        assert_ok!(ProjectRegistry::challenge_campaign(RuntimeOrigin::signed(challenger), 0, evidence));
+        assert_eq!(Balances::reserved_balance(challenger), 50);
+        assert_noop!(
+            ProjectRegistry::challenge_campaign(RuntimeOrigin::signed(challenger), 0, evidence),
+            Error::<Test>::AlreadyChallenged
+        );
+        // The owner cannot walk away with the deposit backing the challenge
+        assert_noop!(
+            ProjectRegistry::cancel_campaign(RuntimeOrigin::signed(owner), 0),
+            Error::<Test>::AlreadyChallenged
+        );
+
+        // Act
+        assert_ok!(ProjectRegistry::resolve_challenge(RuntimeOrigin::root(), 0, true));
+
+        // Assert
+        assert_eq!(Balances::reserved_balance(challenger), 0);
+        assert_eq!(Balances::free_balance(challenger), 1100);
+        assert_eq!(Balances::reserved_balance(owner), 0);
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Cancelled);
+
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::ChallengeUpheld {
+            campaign_id: 0,
+            challenger,
+            reward: 100,
+        }));
+    });
+}
+
+#[test]
+fn dismissed_challenge_slashes_bond() {
+    build_and_execute(|| {
+        let (owner, challenger) = (1, 2);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        let _ = Balances::deposit_creating(&challenger, 1000);
+
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        let evidence = sp_core::H256::repeat_byte(3);
+        assert_ok!(ProjectRegistry::challenge_campaign(RuntimeOrigin::signed(challenger), 0, evidence));
+
+        assert_ok!(ProjectRegistry::resolve_challenge(RuntimeOrigin::root(), 0, false));
+
+        assert_eq!(Balances::free_balance(challenger), 950);
+        assert_eq!(Balances::reserved_balance(challenger), 0);
+        assert_eq!(Balances::reserved_balance(owner), 100);
+        assert!(crate::Challenges::<Test>::get(0).is_none());
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Active);
+    });
+}
+
+#[test]
+fn settle_batch_pays_out_in_batches() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, alice, bob, settler) = (1, 2, 3, 4);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [owner, alice, bob, settler] {
+            let _ = Balances::deposit_creating(&who, 1000);
+        }
+
+        crate::Parameters::<Test>::mutate(|parameters| {
+            parameters.success_fee = sp_runtime::Permill::from_percent(10);
+        });
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            150,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(alice), 0, 300, None, false));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(bob), 0, 300, None, false));
+        assert_noop!(
+            ProjectRegistry::settle_batch(RuntimeOrigin::signed(settler), 0, 10),
+            Error::<Test>::NotFinished
+        );
+        Timestamp::set_timestamp(200);
+        ProjectRegistry::on_initialize(2);
+
+        // Act
+        assert_ok!(ProjectRegistry::settle_batch(RuntimeOrigin::signed(settler), 0, 1));
+        assert!(crate::SettlementCursors::<Test>::contains_key(0));
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Success);
+        assert_ok!(ProjectRegistry::settle_batch(RuntimeOrigin::signed(settler), 0, 10));
+
+        // Assert
+        assert!(!crate::SettlementCursors::<Test>::contains_key(0));
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Settled);
+        assert_eq!(Balances::reserved_balance(alice), 0);
+        assert_eq!(Balances::reserved_balance(bob), 0);
+        // Raise minus the 10% fee; the deposit left after two bounties of 5 stays reserved
+        assert_eq!(Balances::free_balance(owner), 900 + 540);
+        assert_eq!(Balances::reserved_balance(owner), 90);
+        assert_eq!(Balances::free_balance(settler), 1010);
+
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::CampaignSettled {
+            campaign_id: 0,
+        }));
+    });
+}
+
+#[test]
+fn settlement_in_progress_blocks_claim_refund() {
+    build_and_execute(|| {
+        let (owner, alice, bob) = (1, 2, 3);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [owner, alice, bob] {
+            let _ = Balances::deposit_creating(&who, 1000);
+        }
+
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            150,
+            500,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(alice), 0, 100, None, false));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(bob), 0, 100, None, false));
+        Timestamp::set_timestamp(200);
+        ProjectRegistry::on_initialize(2);
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Failed);
+
+        assert_ok!(ProjectRegistry::settle_batch(RuntimeOrigin::signed(owner), 0, 1));
+
+        assert_noop!(
+            ProjectRegistry::claim_refund(RuntimeOrigin::signed(alice), 0),
+            Error::<Test>::SettlementInProgress
+        );
+        assert_ok!(ProjectRegistry::settle_batch(RuntimeOrigin::signed(owner), 0, 1));
+        assert_ok!(ProjectRegistry::settle_batch(RuntimeOrigin::signed(owner), 0, 1));
+        assert_eq!(Balances::free_balance(alice), 1000);
+        assert_eq!(Balances::free_balance(bob), 1000);
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Settled);
+    });
+}
+
+#[test]
+fn force_cancel_rejected_while_settling() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, alice, bob) = (1, 2, 3);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [owner, alice, bob] {
+            let _ = Balances::deposit_creating(&who, 1000);
+        }
+
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            150,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(alice), 0, 100, None, false));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(bob), 0, 100, None, false));
+        Timestamp::set_timestamp(200);
+        ProjectRegistry::on_initialize(2);
+        assert_ok!(ProjectRegistry::settle_batch(RuntimeOrigin::signed(owner), 0, 1));
+
+        // Act & Assert
+        assert_noop!(
+            ProjectRegistry::force_cancel_campaign(RuntimeOrigin::root(), 0, Default::default(), true),
+            Error::<Test>::SettlementInProgress
+        );
+    });
+}
+
+#[test]
+fn settle_batch_rejects_zero_limit() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, alice) = (1, 2);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [owner, alice] {
+            let _ = Balances::deposit_creating(&who, 1000);
+        }
+
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            150,
+            500,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(alice), 0, 100, None, false));
+        Timestamp::set_timestamp(200);
+        ProjectRegistry::on_initialize(2);
+
+        // Act & Assert
+        assert_noop!(
+            ProjectRegistry::settle_batch(RuntimeOrigin::signed(owner), 0, 0),
+            Error::<Test>::ZeroSettlementLimit
+        );
+        assert!(!crate::SettlementCursors::<Test>::contains_key(0));
+        assert_ok!(ProjectRegistry::claim_refund(RuntimeOrigin::signed(alice), 0));
+        assert_eq!(Balances::free_balance(alice), 1000);
+    });
+}
+
+#[test]
+fn payout_delay_leaves_time_to_force_cancel() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, alice, bob) = (1, 2, 3);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        PayoutDelay::set(100);
+        for who in [owner, alice, bob] {
+            let _ = Balances::deposit_creating(&who, 1000);
+        }
+
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            150,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(alice), 0, 100, None, false));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(bob), 0, 100, None, false));
+        Timestamp::set_timestamp(200);
+        ProjectRegistry::on_initialize(2);
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Success);
+
+        // Act & Assert: nothing is paid out before the delay has passed
+        assert_noop!(
+            ProjectRegistry::settle_batch(RuntimeOrigin::signed(owner), 0, 1),
+            Error::<Test>::PayoutDelayNotOver
+        );
+        Timestamp::set_timestamp(249);
+        assert_noop!(
+            ProjectRegistry::settle_batch(RuntimeOrigin::signed(owner), 0, 1),
+            Error::<Test>::PayoutDelayNotOver
+        );
+
+        // Act & Assert: governance can still cancel, and contributions are refunded in full
+        assert_ok!(ProjectRegistry::force_cancel_campaign(RuntimeOrigin::root(), 0, Default::default(), true));
+        assert_ok!(ProjectRegistry::settle_batch(RuntimeOrigin::signed(owner), 0, 2));
+        assert_ok!(ProjectRegistry::settle_batch(RuntimeOrigin::signed(owner), 0, 1));
+        assert_eq!(Balances::free_balance(alice), 1000);
+        assert_eq!(Balances::free_balance(bob), 1000);
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Settled);
+    });
+}
+
+#[test]
+fn payout_delay_ends_after_the_campaign_is_finalized() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, alice) = (1, 2);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        PayoutDelay::set(100);
+        for who in [owner, alice] {
+            let _ = Balances::deposit_creating(&who, 1000);
+        }
+
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            150,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(alice), 0, 100, None, false));
+        Timestamp::set_timestamp(200);
+        ProjectRegistry::on_initialize(2);
+
+        // Act
+        Timestamp::set_timestamp(250);
+        assert_ok!(ProjectRegistry::settle_batch(RuntimeOrigin::signed(alice), 0, 2));
+
+        // Assert
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Settled);
+        assert_eq!(Balances::free_balance(owner), 1000);
+        assert_eq!(Balances::reserved_balance(alice), 0);
+    });
+}
+
+#[test]
+fn challenges_and_settlement_exclude_each_other() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, alice, bob, challenger) = (1, 2, 3, 4);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [owner, alice, bob, challenger] {
+            let _ = Balances::deposit_creating(&who, 1000);
+        }
+
+        for _ in 0..2 {
+            assert_ok!(ProjectRegistry::create_campaign(
+                RuntimeOrigin::signed(owner),
+                round_metadata(),
+                50,
+                150,
+                100,
+                1000,
+                Default::default(),
+                Default::default(),
+                FundingModel::AllOrNothing,
+            ));
+        }
+        for campaign_id in [0, 1] {
+            assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(alice), campaign_id, 100, None, false));
+            assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(bob), campaign_id, 100, None, false));
+        }
+        Timestamp::set_timestamp(200);
+        ProjectRegistry::on_initialize(2);
+        let evidence = sp_core::H256::repeat_byte(3);
+
+        // Act & Assert
+        // A challenged campaign is not paid out until the challenge is resolved
+        assert_ok!(ProjectRegistry::challenge_campaign(RuntimeOrigin::signed(challenger), 0, evidence));
+        assert_noop!(
+            ProjectRegistry::settle_batch(RuntimeOrigin::signed(owner), 0, 1),
+            Error::<Test>::AlreadyChallenged
+        );
+        assert_ok!(ProjectRegistry::resolve_challenge(RuntimeOrigin::root(), 0, false));
+        assert_ok!(ProjectRegistry::settle_batch(RuntimeOrigin::signed(owner), 0, 1));
+
+        // A campaign being paid out can no longer be challenged
+        assert_ok!(ProjectRegistry::settle_batch(RuntimeOrigin::signed(owner), 1, 1));
+        assert_noop!(
+            ProjectRegistry::challenge_campaign(RuntimeOrigin::signed(challenger), 1, evidence),
+            Error::<Test>::SettlementInProgress
+        );
+    });
+}
+
+#[test]
+fn fund_round_from_treasury_respects_spend_limit() {
+    build_and_execute(|| {
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&TreasuryAccount::get(), 5000);
+
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 1000, None));
+        assert_noop!(
+            ProjectRegistry::fund_round_from_treasury(RuntimeOrigin::root(), 0, 1001),
+            Error::<Test>::InsufficientPermission
+        );
+
+        assert_ok!(ProjectRegistry::fund_round_from_treasury(RuntimeOrigin::root(), 0, 600));
+        assert_ok!(ProjectRegistry::fund_round_from_treasury(RuntimeOrigin::root(), 0, 400));
+
+        assert_eq!(crate::Rounds::<Test>::get(0).unwrap().matching_pool, 1000);
+        assert_eq!(Balances::free_balance(ProjectRegistry::account_id()), 1000);
+        assert_eq!(Balances::free_balance(TreasuryAccount::get()), 4000);
+
+        // The limit applies to the period, not to each call
+        assert_noop!(
+            ProjectRegistry::fund_round_from_treasury(RuntimeOrigin::root(), 0, 1),
+            Error::<Test>::InsufficientPermission
+        );
+
+        // The next period starts afresh
+        Timestamp::set_timestamp(TreasurySpendPeriod::get());
+        assert_ok!(ProjectRegistry::fund_round_from_treasury(RuntimeOrigin::root(), 0, 1000));
+        assert_eq!(crate::Rounds::<Test>::get(0).unwrap().matching_pool, 2000);
+        assert_eq!(Balances::free_balance(TreasuryAccount::get()), 3000);
+    });
+}
+
+#[test]
+fn reap_campaign_archives_summary_after_retention() {
+    let mut ext = new_test_ext();
+    ext.execute_with(|| {
+        // Arrange
+        let (owner, alice, bob) = (1, 2, 3);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [owner, alice, bob] {
+            let _ = Balances::deposit_creating(&who, 1000);
+        }
+
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            150,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(alice), 0, 300, None, false));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(bob), 0, 200, None, false));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(bob), 0, 100, None, false));
+        Timestamp::set_timestamp(200);
+        ProjectRegistry::on_initialize(2);
+        assert_noop!(
+            ProjectRegistry::reap_campaign(RuntimeOrigin::signed(alice), 0),
+            Error::<Test>::NotSettled
+        );
+        assert_ok!(ProjectRegistry::settle_batch(RuntimeOrigin::signed(alice), 0, 10));
+        assert_noop!(
+            ProjectRegistry::reap_campaign(RuntimeOrigin::signed(alice), 0),
+            Error::<Test>::RetentionPeriodNotOver
+        );
+    });
+
+    // Act
+    // `clear_prefix` only counts keys already in the backend against its limit, so every call
+    // is committed as if it were made in its own block
+    let reap = |ext: &mut sp_io::TestExternalities| {
+        ext.commit_all().unwrap();
+        ext.execute_with(|| {
+            Timestamp::set_timestamp(1200);
+            assert_ok!(ProjectRegistry::reap_campaign(RuntimeOrigin::signed(2), 0));
+        });
+    };
+    // `MaxReapBatch` is 1, so the two contributions take more than one call
+    reap(&mut ext);
+    ext.execute_with(|| {
+        assert!(crate::Campaigns::<Test>::get(0).is_some());
+        assert!(crate::ReapCursors::<Test>::contains_key(0));
+    });
+    for _ in 0..2 {
+        if ext.execute_with(|| crate::Campaigns::<Test>::contains_key(0)) {
+            reap(&mut ext);
+        }
+    }
+
+    ext.execute_with(|| {
+        let owner = 1;
+
+        // Assert
+        assert!(crate::Campaigns::<Test>::get(0).is_none());
+        assert_eq!(crate::CampaignContributions::<Test>::iter_prefix(0).count(), 0);
+        assert!(ProjectRegistry::campaigns_by_owner(&owner).is_empty());
+        assert!(ProjectRegistry::campaigns_by_category(Category::Other).is_empty());
+        assert_eq!(Balances::reserved_balance(owner), 0);
+        assert_eq!(
+            crate::CampaignHistory::<Test>::get(0),
+            Some(crate::CampaignSummary {
+                owner,
+                raised: 600,
+                contributors: 2,
+                matching: 0,
+                settled_at: 200,
+            })
+        );
+
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::CampaignReaped {
+            campaign_id: 0,
+        }));
+        ProjectRegistry::do_try_state().expect("registry invariants hold");
+    });
+}
+
+#[test]
+fn reap_campaign_clears_round_references() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor, sponsor) = (1, 2, 4);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [owner, contributor, sponsor] {
+            let _ = Balances::deposit_creating(&who, 2000);
+        }
+
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 1000, None));
+        assert_ok!(ProjectRegistry::fund_round(RuntimeOrigin::signed(sponsor), 0, 1000));
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 0, 0));
+        assert_ok!(ProjectRegistry::approve_application(RuntimeOrigin::root(), 0, 0));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 400, None, false));
+        Timestamp::set_timestamp(300);
+        ProjectRegistry::on_initialize(2);
+        assert_ok!(ProjectRegistry::settle_batch(RuntimeOrigin::signed(owner), 0, 10));
+
+        Timestamp::set_timestamp(1300);
+        assert_noop!(
+            ProjectRegistry::reap_campaign(RuntimeOrigin::signed(contributor), 0),
+            Error::<Test>::RoundNotFinalized
+        );
+        assert_ok!(ProjectRegistry::finalize_round(RuntimeOrigin::signed(sponsor), 0));
+
+        // Act
+        assert_ok!(ProjectRegistry::reap_campaign(RuntimeOrigin::signed(contributor), 0));
+
+        // Assert
+        assert!(crate::Campaigns::<Test>::get(0).is_none());
+        assert!(crate::RoundApplications::<Test>::get(0, 0).is_none());
+        assert!(crate::RoundCampaigns::<Test>::get(0).is_empty());
+        assert!(crate::CampaignRound::<Test>::get(0).is_none());
+        assert!(!crate::RoundMatches::<Test>::contains_key(0, 0));
+        assert_eq!(crate::CampaignHistory::<Test>::get(0).unwrap().matching, 1000);
+    });
+}
+
+#[test]
+fn private_round_counts_only_revealed_contributions() {
+    use sp_runtime::traits::Hash;
+
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor, lurker) = (1, 2, 3);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [owner, contributor, lurker] {
+            let _ = Balances::deposit_creating(&who, 1000);
+        }
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 1000, Some(100)));
+        assert_ok!(ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 0, 0));
+        assert_ok!(ProjectRegistry::approve_application(RuntimeOrigin::root(), 0, 0));
+
+        let salt = [7u8; 32];
+        let commitment = <Test as frame_system::Config>::Hashing::hash_of(&(150u64, salt));
+
+        // Act
+        assert_noop!(
+            ProjectRegistry::contribute(RuntimeOrigin::signed(lurker), 0, 100, None, false),
+            Error::<Test>::PrivateCampaign
+        );
+        assert_ok!(ProjectRegistry::commit_contribution(RuntimeOrigin::signed(contributor), 0, commitment, 400));
+        assert_ok!(ProjectRegistry::commit_contribution(RuntimeOrigin::signed(lurker), 0, commitment, 100));
+        assert_eq!(Balances::reserved_balance(contributor), 400);
+        assert_noop!(
+            ProjectRegistry::reveal_contribution(RuntimeOrigin::signed(contributor), 0, 150, salt),
+            Error::<Test>::RevealClosed
+        );
+
+        // The campaign stays active through the reveal window
+        Timestamp::set_timestamp(300);
+        ProjectRegistry::on_initialize(2);
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Active);
+
+        assert_noop!(
+            ProjectRegistry::reveal_contribution(RuntimeOrigin::signed(contributor), 0, 200, salt),
+            Error::<Test>::InvalidReveal
+        );
+        assert_ok!(ProjectRegistry::reveal_contribution(RuntimeOrigin::signed(contributor), 0, 150, salt));
+        assert_noop!(
+            ProjectRegistry::withdraw_commitment(RuntimeOrigin::signed(lurker), 0),
+            Error::<Test>::RevealOpen
+        );
+
+        Timestamp::set_timestamp(400);
+        ProjectRegistry::on_initialize(3);
+
+        // Assert
+        let campaign = crate::Campaigns::<Test>::get(0).unwrap();
+        assert_eq!(campaign.status, CampaignStatus::Success);
+        assert_eq!(campaign.matched, 150);
+        assert_eq!(campaign.contributors, 1);
+        assert_eq!(crate::CampaignContributions::<Test>::get(0, contributor), 150);
+        assert_eq!(Balances::reserved_balance(contributor), 150);
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::ContributionRevealed {
+            campaign_id: 0,
+            who: contributor,
+            amount: 150,
+            refunded: 250,
+        }));
+
+        // Unrevealed commitments are released once the window has closed
+        assert_ok!(ProjectRegistry::withdraw_commitment(RuntimeOrigin::signed(lurker), 0));
+        assert_eq!(Balances::reserved_balance(lurker), 0);
+        assert_eq!(crate::CampaignContributions::<Test>::get(0, lurker), 0);
+    });
+}
+
+#[test]
+fn private_round_waits_for_reveal_window_before_finalizing() {
+    use sp_runtime::traits::Hash;
+
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor, sponsor) = (1, 2, 3);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [owner, contributor, sponsor] {
+            let _ = Balances::deposit_creating(&who, 2000);
+        }
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 300, Some(100)));
+        assert_ok!(ProjectRegistry::fund_round(RuntimeOrigin::signed(sponsor), 0, 1000));
+        assert_ok!(ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 0, 0));
+        assert_ok!(ProjectRegistry::approve_application(RuntimeOrigin::root(), 0, 0));
+
+        let salt = [7u8; 32];
+        let commitment = <Test as frame_system::Config>::Hashing::hash_of(&(150u64, salt));
+        assert_ok!(ProjectRegistry::commit_contribution(RuntimeOrigin::signed(contributor), 0, commitment, 400));
+
+        Timestamp::set_timestamp(300);
+        ProjectRegistry::on_initialize(2);
+
+        // Act & Assert
+        assert_noop!(
+            ProjectRegistry::finalize_round(RuntimeOrigin::signed(sponsor), 0),
+            Error::<Test>::RoundCampaignsPending
+        );
+
+        assert_ok!(ProjectRegistry::reveal_contribution(RuntimeOrigin::signed(contributor), 0, 150, salt));
+        Timestamp::set_timestamp(400);
+        ProjectRegistry::on_initialize(3);
+        assert_ok!(ProjectRegistry::finalize_round(RuntimeOrigin::signed(sponsor), 0));
+
+        assert_eq!(crate::Rounds::<Test>::get(0).unwrap().status, crate::RoundStatus::Finalized);
+        assert!(crate::RoundMatches::<Test>::get(0, 0) > 0);
+    });
+}
+
+#[test]
+fn only_approved_fresh_campaigns_turn_private() {
+    use sp_runtime::traits::Hash;
+
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor) = (1, 2);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [owner, contributor] {
+            let _ = Balances::deposit_creating(&who, 1000);
+        }
+        for _ in 0..2 {
+            assert_ok!(ProjectRegistry::create_campaign(
+                RuntimeOrigin::signed(owner),
+                round_metadata(),
+                50,
+                300,
+                100,
+                1000,
+                Default::default(),
+                Default::default(),
+                FundingModel::AllOrNothing,
+            ));
+        }
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 1000, Some(100)));
+        let commitment = <Test as frame_system::Config>::Hashing::hash_of(&(150u64, [7u8; 32]));
+
+        // Act & Assert: public contributions cannot be sealed after the fact
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 100, None, false));
+        assert_noop!(
+            ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 0, 0),
+            Error::<Test>::CampaignHasContributions
+        );
+
+        // Act & Assert: a pending campaign takes neither kind of contribution
+        assert_ok!(ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 0, 1));
+        assert_eq!(ProjectRegistry::reveal_window(1), None);
+        assert_noop!(
+            ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 1, 100, None, false),
+            Error::<Test>::PrivateApplicationPending
+        );
+        assert_noop!(
+            ProjectRegistry::commit_contribution(RuntimeOrigin::signed(contributor), 1, commitment, 200),
+            Error::<Test>::NotPrivate
+        );
+
+        // Act & Assert: once rejected, the campaign is public again
+        assert_ok!(ProjectRegistry::reject_application(RuntimeOrigin::root(), 0, 1, Default::default()));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 1, 100, None, false));
+        assert_eq!(crate::CampaignContributions::<Test>::get(1, contributor), 100);
+    });
+}
+
+#[test]
+fn revealed_amount_below_minimum_after_clamping_is_refunded() {
+    use sp_runtime::traits::Hash;
+
+    build_and_execute(|| {
+        // Arrange
+        let (owner, alice, bob) = (1, 2, 3);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [owner, alice, bob] {
+            let _ = Balances::deposit_creating(&who, 2000);
+        }
+        crate::Parameters::<Test>::mutate(|parameters| parameters.min_contribution = 50);
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 1000, Some(100)));
+        assert_ok!(ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 0, 0));
+        assert_ok!(ProjectRegistry::approve_application(RuntimeOrigin::root(), 0, 0));
+
+        let salt = [7u8; 32];
+        let hash = |amount: u64| <Test as frame_system::Config>::Hashing::hash_of(&(amount, salt));
+        assert_ok!(ProjectRegistry::commit_contribution(RuntimeOrigin::signed(alice), 0, hash(980), 1000));
+        assert_ok!(ProjectRegistry::commit_contribution(RuntimeOrigin::signed(bob), 0, hash(100), 100));
+        Timestamp::set_timestamp(300);
+        ProjectRegistry::on_initialize(2);
+        assert_ok!(ProjectRegistry::reveal_contribution(RuntimeOrigin::signed(alice), 0, 980, salt));
+
+        // Act: only 20 of bob's 100 would fit under the hard cap
+        assert_ok!(ProjectRegistry::reveal_contribution(RuntimeOrigin::signed(bob), 0, 100, salt));
+
+        // Assert
+        assert_eq!(crate::CampaignContributions::<Test>::get(0, bob), 0);
+        assert_eq!(Balances::reserved_balance(bob), 0);
+        let campaign = crate::Campaigns::<Test>::get(0).unwrap();
+        assert_eq!((campaign.matched, campaign.contributors), (980, 1));
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::ContributionRevealed {
+            campaign_id: 0,
+            who: bob,
+            amount: 0,
+            refunded: 100,
+        }));
+    });
+}
+
+#[test]
+fn anti_collusion_round_pays_out_verified_tally() {
+    use crate::tally::{InsecureHashVerifier, TallyEntry, TallyInputs};
+
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor, coordinator, sponsor) = (1, 2, 5, 6);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        let _ = Balances::deposit_creating(&contributor, 1000);
+        let _ = Balances::deposit_creating(&sponsor, 2000);
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 1000, None));
+        assert_ok!(ProjectRegistry::fund_round(RuntimeOrigin::signed(sponsor), 0, 1000));
+        assert_ok!(ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 0, 0));
+        assert_ok!(ProjectRegistry::approve_application(RuntimeOrigin::root(), 0, 0));
+
+        // Act
+        assert_ok!(ProjectRegistry::set_round_coordinator(RuntimeOrigin::root(), 0, coordinator, [1; 32]));
+        assert_ok!(ProjectRegistry::signup(RuntimeOrigin::signed(contributor), 0, [2; 32], 300));
+        assert_eq!(Balances::free_balance(contributor), 700);
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 100, None, false));
+
+        let message: crate::MessageOf<Test> = make_bounded_string("encrypted vote");
+        assert_noop!(
+            ProjectRegistry::publish_message(RuntimeOrigin::signed(contributor), 0, message.clone()),
+            Error::<Test>::OutsideVotingPeriod
+        );
+        Timestamp::set_timestamp(200);
+        assert_ok!(ProjectRegistry::publish_message(RuntimeOrigin::signed(contributor), 0, message));
+        assert_noop!(
+            ProjectRegistry::set_round_coordinator(RuntimeOrigin::root(), 0, contributor, [3; 32]),
+            Error::<Test>::CoordinatorLocked
+        );
+
+        Timestamp::set_timestamp(1000);
+        ProjectRegistry::on_initialize(2);
+
+        let coordination = crate::Coordinations::<Test>::get(0).unwrap();
+        assert_eq!((coordination.signups, coordination.messages, coordination.budget), (1, 1, 300));
+        let inputs = TallyInputs {
+            coordinator_key: coordination.public_key,
+            signup_chain: coordination.signup_chain,
+            signups: coordination.signups,
+            message_chain: coordination.message_chain,
+            messages: coordination.messages,
+            budget: coordination.budget,
+        };
+        let tally: BoundedVec<_, _> =
+            vec![TallyEntry { campaign_id: 0, spent: 200, root_sum: 14 }].try_into().unwrap();
+        let overspent: BoundedVec<_, _> =
+            vec![TallyEntry { campaign_id: 0, spent: 400, root_sum: 20 }].try_into().unwrap();
+        let proof = InsecureHashVerifier::prove(&inputs, &tally);
+
+        assert_noop!(
+            ProjectRegistry::submit_tally(RuntimeOrigin::signed(contributor), 0, tally.clone(), proof),
+            Error::<Test>::NotCoordinator
+        );
+        assert_noop!(
+            ProjectRegistry::submit_tally(RuntimeOrigin::signed(coordinator), 0, tally.clone(), [0; 32]),
+            Error::<Test>::InvalidTally
+        );
+        assert_noop!(
+            ProjectRegistry::submit_tally(
+                RuntimeOrigin::signed(coordinator),
+                0,
+                overspent.clone(),
+                InsecureHashVerifier::prove(&inputs, &overspent),
+            ),
+            Error::<Test>::InvalidTally
+        );
+        assert_noop!(
+            ProjectRegistry::finalize_round(RuntimeOrigin::signed(sponsor), 0),
+            Error::<Test>::TallyMissing
+        );
+        assert_ok!(ProjectRegistry::submit_tally(RuntimeOrigin::signed(coordinator), 0, tally, proof));
+        assert_ok!(ProjectRegistry::finalize_round(RuntimeOrigin::signed(sponsor), 0));
+
+        // Assert
+        // The unallocated 100 goes back to the contributor instead of the matching pool
+        assert_eq!(crate::RoundMatches::<Test>::get(0, 0), 1000);
+        assert_eq!(Balances::free_balance(owner), 900 + 200 + 1000);
+        assert_eq!(Balances::free_balance(contributor), 1000 - 300 - 100 + 100);
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::TallyAllocated {
+            round_id: 0,
+            campaign_id: 0,
+            amount: 200,
+        }));
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::SignupRefunded {
+            round_id: 0,
+            who: contributor,
+            amount: 100,
+        }));
+    });
+}
+
+#[test]
+fn failed_campaign_receives_no_tally_payout() {
+    use crate::tally::{InsecureHashVerifier, TallyEntry, TallyInputs};
+
+    build_and_execute(|| {
+        // Arrange
+        let (owner, voter, coordinator, sponsor) = (1, 3, 5, 6);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [owner, voter, sponsor] {
+            let _ = Balances::deposit_creating(&who, 2000);
+        }
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 1000, None));
+        assert_ok!(ProjectRegistry::fund_round(RuntimeOrigin::signed(sponsor), 0, 1000));
+        assert_ok!(ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 0, 0));
+        assert_ok!(ProjectRegistry::approve_application(RuntimeOrigin::root(), 0, 0));
+        assert_ok!(ProjectRegistry::set_round_coordinator(RuntimeOrigin::root(), 0, coordinator, [1; 32]));
+        assert_ok!(ProjectRegistry::signup(RuntimeOrigin::signed(voter), 0, [2; 32], 300));
+
+        // No contributions reach the soft cap
+        Timestamp::set_timestamp(1000);
+        ProjectRegistry::on_initialize(2);
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Failed);
+
+        let coordination = crate::Coordinations::<Test>::get(0).unwrap();
+        let inputs = TallyInputs {
+            coordinator_key: coordination.public_key,
+            signup_chain: coordination.signup_chain,
+            signups: coordination.signups,
+            message_chain: coordination.message_chain,
+            messages: coordination.messages,
+            budget: coordination.budget,
+        };
+        let tally: BoundedVec<_, _> =
+            vec![TallyEntry { campaign_id: 0, spent: 200, root_sum: 14 }].try_into().unwrap();
+        let proof = InsecureHashVerifier::prove(&inputs, &tally);
+        assert_ok!(ProjectRegistry::submit_tally(RuntimeOrigin::signed(coordinator), 0, tally, proof));
+
+        // Act
+        assert_ok!(ProjectRegistry::finalize_round(RuntimeOrigin::signed(sponsor), 0));
+
+        // Assert
+        assert_eq!(crate::RoundMatches::<Test>::get(0, 0), 0);
+        assert_eq!(Balances::free_balance(owner), 2000 - 100);
+        assert_eq!(Balances::free_balance(voter), 2000);
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::RoundSwept {
+            round_id: 0,
+            amount: 1000,
+        }));
+    });
+}
+
+#[test]
+fn finalize_round_refunds_unspent_signup_budgets() {
+    use crate::tally::{InsecureHashVerifier, TallyEntry, TallyInputs};
+
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor, voter_a, voter_b, voter_c, coordinator, sponsor) = (1, 2, 3, 4, 7, 5, 6);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [owner, contributor, voter_a, voter_b, voter_c, sponsor] {
+            let _ = Balances::deposit_creating(&who, 2000);
+        }
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 1000, None));
+        assert_ok!(ProjectRegistry::fund_round(RuntimeOrigin::signed(sponsor), 0, 1000));
+        assert_ok!(ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 0, 0));
+        assert_ok!(ProjectRegistry::approve_application(RuntimeOrigin::root(), 0, 0));
+        assert_ok!(ProjectRegistry::set_round_coordinator(RuntimeOrigin::root(), 0, coordinator, [1; 32]));
+        assert_ok!(ProjectRegistry::signup(RuntimeOrigin::signed(voter_a), 0, [2; 32], 100));
+        assert_ok!(ProjectRegistry::signup(RuntimeOrigin::signed(voter_b), 0, [3; 32], 200));
+        assert_ok!(ProjectRegistry::signup(RuntimeOrigin::signed(voter_c), 0, [4; 32], 1));
+        assert_noop!(
+            ProjectRegistry::signup(RuntimeOrigin::signed(voter_a), 0, [2; 32], 100),
+            Error::<Test>::TooManySignups
+        );
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 100, None, false));
+
+        Timestamp::set_timestamp(1000);
+        ProjectRegistry::on_initialize(2);
+        let coordination = crate::Coordinations::<Test>::get(0).unwrap();
+        let inputs = TallyInputs {
+            coordinator_key: coordination.public_key,
+            signup_chain: coordination.signup_chain,
+            signups: coordination.signups,
+            message_chain: coordination.message_chain,
+            messages: coordination.messages,
+            budget: coordination.budget,
+        };
+        let tally: BoundedVec<_, _> =
+            vec![TallyEntry { campaign_id: 0, spent: 150, root_sum: 12 }].try_into().unwrap();
+        let proof = InsecureHashVerifier::prove(&inputs, &tally);
+        assert_ok!(ProjectRegistry::submit_tally(RuntimeOrigin::signed(coordinator), 0, tally, proof));
+
+        // Act
+        assert_ok!(ProjectRegistry::finalize_round(RuntimeOrigin::signed(sponsor), 0));
+
+        // Assert
+        // The unspent 151 of the 301 budget is split by budget, rounding down: 50, 100 and 0
+        assert_eq!(Balances::free_balance(voter_a), 2000 - 100 + 50);
+        assert_eq!(Balances::free_balance(voter_b), 2000 - 200 + 100);
+        assert_eq!(Balances::free_balance(voter_c), 2000 - 1);
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::SignupRefunded {
+            round_id: 0,
+            who: voter_b,
+            amount: 100,
+        }));
+        // The dust of 1 is matched along with the pool, leaving nothing in the pallet account
+        assert_eq!(crate::RoundMatches::<Test>::get(0, 0), 1001);
+        assert_eq!(Balances::free_balance(owner), 2000 - 100 + 150 + 1001);
+        assert_eq!(Balances::free_balance(ProjectRegistry::account_id()), 0);
+        assert!(crate::RoundSignups::<Test>::get(0).is_empty());
+    });
+}
+
+#[test]
+fn anti_collusion_round_without_tally_finalizes_after_timeout() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor, voter, coordinator, sponsor) = (1, 2, 3, 5, 6);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [owner, contributor, voter, sponsor] {
+            let _ = Balances::deposit_creating(&who, 2000);
+        }
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 1000, None));
+        assert_ok!(ProjectRegistry::fund_round(RuntimeOrigin::signed(sponsor), 0, 1000));
+        assert_ok!(ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 0, 0));
+        assert_ok!(ProjectRegistry::approve_application(RuntimeOrigin::root(), 0, 0));
+        assert_ok!(ProjectRegistry::set_round_coordinator(RuntimeOrigin::root(), 0, coordinator, [1; 32]));
+        assert_ok!(ProjectRegistry::signup(RuntimeOrigin::signed(voter), 0, [2; 32], 300));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 400, None, false));
+
+        Timestamp::set_timestamp(1000);
+        ProjectRegistry::on_initialize(2);
+        Timestamp::set_timestamp(1499);
+        assert_noop!(
+            ProjectRegistry::finalize_round(RuntimeOrigin::signed(sponsor), 0),
+            Error::<Test>::TallyMissing
+        );
+
+        // Act
+        Timestamp::set_timestamp(1500);
+        assert_ok!(ProjectRegistry::finalize_round(RuntimeOrigin::signed(sponsor), 0));
+
+        // Assert
+        // The signed-up budget is returned and the pool is matched on contributions
+        assert_eq!(crate::RoundMatches::<Test>::get(0, 0), 1000);
+        assert_eq!(Balances::free_balance(owner), 2000 - 100 + 1000);
+        assert_eq!(Balances::free_balance(voter), 2000);
+        assert_eq!(crate::Rounds::<Test>::get(0).unwrap().status, crate::RoundStatus::Finalized);
+    });
+}
+
+#[test]
+fn unit_tally_verifier_disables_coordination() {
+    use crate::tally::{InsecureHashVerifier, TallyVerifier};
+
+    const { assert!(!<() as TallyVerifier<sp_core::H256, u64>>::ENABLED) };
+    const { assert!(<InsecureHashVerifier as TallyVerifier<sp_core::H256, u64>>::ENABLED) };
+}
+
+#[test]
+fn rejected_campaign_cannot_reapply_to_the_same_round() {
+    build_and_execute(|| {
+        // Arrange
+        let owner = 1;
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 2000);
+
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 1000, None));
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 1000, None));
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        let reason = sp_core::H256::repeat_byte(1);
+        assert_ok!(ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 0, 0));
+        assert_ok!(ProjectRegistry::reject_application(RuntimeOrigin::root(), 0, 0, reason));
+
+        // Act & Assert
+        assert_noop!(
+            ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 0, 0),
+            Error::<Test>::AlreadyApplied
+        );
+        assert_eq!(
+            crate::RoundApplications::<Test>::get(0, 0),
+            Some(crate::ApplicationStatus::Rejected { reason })
+        );
+        // Other rounds are still open to the campaign
+        assert_ok!(ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 1, 0));
+    });
+}
+
+#[test]
+fn cancelled_campaign_cannot_be_approved() {
+    build_and_execute(|| {
+        // Arrange
+        let owner = 1;
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 1000, None));
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 0, 0));
+        assert_ok!(ProjectRegistry::cancel_campaign(RuntimeOrigin::signed(owner), 0));
+
+        // Act & Assert
+        assert_noop!(
+            ProjectRegistry::approve_application(RuntimeOrigin::root(), 0, 0),
+            Error::<Test>::AlreadyFinalized
+        );
+        assert!(crate::RoundCampaigns::<Test>::get(0).is_empty());
+        assert_eq!(crate::RoundApplications::<Test>::get(0, 0), Some(crate::ApplicationStatus::Pending));
+    });
+}
+
+#[test]
+fn contributions_before_the_round_start_count_toward_the_match() {
+    build_and_execute(|| {
+        // Arrange
+        let (early_owner, late_owner, contributor, sponsor) = (1, 3, 2, 4);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [early_owner, late_owner, contributor, sponsor] {
+            let _ = Balances::deposit_creating(&who, 2000);
+        }
+
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 1000, None));
+        assert_ok!(ProjectRegistry::fund_round(RuntimeOrigin::signed(sponsor), 0, 1000));
+        for (campaign_id, owner) in [early_owner, late_owner].into_iter().enumerate() {
+            let campaign_id = campaign_id as u32;
+            assert_ok!(ProjectRegistry::create_campaign(
+                RuntimeOrigin::signed(owner),
+                round_metadata(),
+                50,
+                300,
+                100,
+                1000,
+                Default::default(),
+                Default::default(),
+                FundingModel::AllOrNothing,
+            ));
+            assert_ok!(ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 0, campaign_id));
+            assert_ok!(ProjectRegistry::approve_application(RuntimeOrigin::root(), 0, campaign_id));
+        }
+
+        // Act
+        // The round starts at 200
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 400, None, false));
+        Timestamp::set_timestamp(250);
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 1, 400, None, false));
+        Timestamp::set_timestamp(1000);
+        ProjectRegistry::on_initialize(2);
+        assert_ok!(ProjectRegistry::finalize_round(RuntimeOrigin::signed(sponsor), 0));
+
+        // Assert
+        assert_eq!(crate::RoundMatches::<Test>::get(0, 0), 500);
+        assert_eq!(crate::RoundMatches::<Test>::get(0, 1), 500);
+    });
+}
+
+#[test]
+fn settled_campaigns_keep_their_match() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor, sponsor) = (1, 2, 4);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [owner, contributor, sponsor] {
+            let _ = Balances::deposit_creating(&who, 2000);
+        }
+
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 1000, None));
+        assert_ok!(ProjectRegistry::fund_round(RuntimeOrigin::signed(sponsor), 0, 1000));
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 0, 0));
+        assert_ok!(ProjectRegistry::approve_application(RuntimeOrigin::root(), 0, 0));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 400, None, false));
+
+        Timestamp::set_timestamp(1000);
+        ProjectRegistry::on_initialize(2);
+        assert_ok!(ProjectRegistry::settle_batch(RuntimeOrigin::signed(owner), 0, 10));
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().status, CampaignStatus::Settled);
+
+        // Act
+        assert_ok!(ProjectRegistry::finalize_round(RuntimeOrigin::signed(sponsor), 0));
+
+        // Assert
+        assert_eq!(crate::RoundMatches::<Test>::get(0, 0), 1000);
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::MatchAllocated {
+            round_id: 0,
+            campaign_id: 0,
+            amount: 1000,
+        }));
+    });
+}
+
+#[test]
+fn finalize_round_returns_unallocated_matching_to_treasury() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor, sponsor) = (1, 2, 4);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [owner, contributor, sponsor] {
+            let _ = Balances::deposit_creating(&who, 2000);
+        }
+
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 1000, None));
+        crate::Rounds::<Test>::mutate(0, |round| {
+            round.as_mut().unwrap().match_cap = sp_runtime::Perbill::from_percent(60)
+        });
+        assert_ok!(ProjectRegistry::fund_round(RuntimeOrigin::signed(sponsor), 0, 1000));
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 0, 0));
+        assert_ok!(ProjectRegistry::approve_application(RuntimeOrigin::root(), 0, 0));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 100, None, false));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 300, None, false));
+        assert_eq!(crate::CampaignRootSums::<Test>::get(0), 20);
+
+        Timestamp::set_timestamp(1000);
+        ProjectRegistry::on_initialize(2);
+
+        // Act
+        assert_ok!(ProjectRegistry::finalize_round(RuntimeOrigin::signed(sponsor), 0));
+
+        // Assert
+        assert_eq!(crate::RoundMatches::<Test>::get(0, 0), 600);
+        assert_eq!(Balances::free_balance(TreasuryAccount::get()), 400);
+        assert_eq!(Balances::free_balance(ProjectRegistry::account_id()), 0);
+        System::assert_has_event(RuntimeEvent::ProjectRegistry(Event::RoundSwept {
+            round_id: 0,
+            amount: 400,
+        }));
+    });
+}
+
+#[test]
+fn finalize_round_waits_for_open_challenges() {
+    build_and_execute(|| {
+        // Arrange
+        let (owner, contributor, challenger, sponsor) = (1, 2, 3, 4);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        for who in [owner, contributor, challenger, sponsor] {
+            let _ = Balances::deposit_creating(&who, 2000);
+        }
+
+        assert_ok!(ProjectRegistry::create_round(RuntimeOrigin::root(), 200, 1000, None));
+        assert_ok!(ProjectRegistry::fund_round(RuntimeOrigin::signed(sponsor), 0, 1000));
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::apply_to_round(RuntimeOrigin::signed(owner), 0, 0));
+        assert_ok!(ProjectRegistry::approve_application(RuntimeOrigin::root(), 0, 0));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 400, None, false));
+        let evidence = sp_core::H256::repeat_byte(3);
+        assert_ok!(ProjectRegistry::challenge_campaign(RuntimeOrigin::signed(challenger), 0, evidence));
+
+        Timestamp::set_timestamp(1000);
+        ProjectRegistry::on_initialize(2);
+
+        // Act & Assert: the challenged campaign holds up the round
+        assert_noop!(
+            ProjectRegistry::finalize_round(RuntimeOrigin::signed(sponsor), 0),
+            Error::<Test>::RoundCampaignsChallenged
+        );
+
+        // Act & Assert: once dismissed, the campaign is matched in full
+        assert_ok!(ProjectRegistry::resolve_challenge(RuntimeOrigin::root(), 0, false));
+        assert_ok!(ProjectRegistry::finalize_round(RuntimeOrigin::signed(sponsor), 0));
+        assert_eq!(crate::RoundMatches::<Test>::get(0, 0), 1000);
+        assert_eq!(Balances::free_balance(owner), 2900);
+        assert_eq!(Balances::free_balance(ProjectRegistry::account_id()), 0);
+    });
+}
+
+fn put_v0_campaigns() {
+    use codec::Encode;
+
+    let _ = Balances::deposit_creating(&1, 1000);
+    let _ = Balances::deposit_creating(&2, 1000);
+    let _ = Balances::deposit_creating(&3, 1000);
+    assert_ok!(Balances::reserve(&1, 100));
+    assert_ok!(Balances::reserve(&2, 200));
+    assert_ok!(Balances::reserve(&3, 150));
+    let active = (1u64, old_metadata(), 50u64, 300u64, 100u64, 1000u64, 300u64, CampaignStatus::Active);
+    frame_support::storage::unhashed::put_raw(&crate::Campaigns::<Test>::hashed_key_for(0), &active.encode());
+    crate::CampaignContributions::<Test>::insert(0, 2, 200);
+    crate::CampaignContributions::<Test>::insert(0, 3, 100);
+    // Version 0 cancelled campaigns without removing them from the active list, and refunds
+    // did not lower `matched`
+    let cancelled = (1u64, old_metadata(), 50u64, 300u64, 100u64, 1000u64, 150u64, CampaignStatus::Cancelled);
+    frame_support::storage::unhashed::put_raw(&crate::Campaigns::<Test>::hashed_key_for(1), &cancelled.encode());
+    crate::CampaignContributions::<Test>::insert(1, 3, 50);
+    crate::ActiveCampaigns::<Test>::put(BoundedVec::try_from(vec![0, 1]).unwrap());
+}
+
+frame_support::parameter_types! {
+    pub OldParameters: crate::ParametersOf<Test> = crate::RegistryParameters {
+        min_contribution: 1,
+        match_cap: sp_runtime::Perbill::one(),
+        challenge_bond: 50,
+        success_fee: sp_runtime::Permill::zero(),
+        settlement_bounty: 5,
+    };
+    pub const OldCampaignDeposit: u64 = 100;
+}
+
+type MigrateV0ToV1 = crate::migrations::v1::LazyMigrationV0ToV1<Test, OldParameters, OldCampaignDeposit>;
+
+#[test]
+fn migrate_v0_to_v1_moves_campaigns_to_the_current_layout() {
+    use frame_support::{
+        migrations::SteppedMigration,
+        traits::{GetStorageVersion, StorageVersion},
+        weights::WeightMeter,
+    };
+
+    new_test_ext().execute_with(|| {
+        // Arrange
+        StorageVersion::new(0).put::<ProjectRegistry>();
+        crate::Parameters::<Test>::kill();
+        put_v0_campaigns();
+
+        // Act
+        let cursor = MigrateV0ToV1::step(None, &mut WeightMeter::new()).unwrap();
+
+        // Assert
+        assert_eq!(cursor, None);
+        let campaign = crate::Campaigns::<Test>::get(0).unwrap();
+        assert_eq!(campaign.deposit, 100);
+        assert_eq!(campaign.contributors, 2);
+        assert_eq!(campaign.matched, 300);
+        assert_eq!(campaign.metadata, round_metadata());
+        assert_eq!(campaign.funding_model, FundingModel::AllOrNothing);
+        assert_eq!(crate::CampaignRootSums::<Test>::get(0), 24);
+        assert!(crate::CampaignsByCategory::<Test>::contains_key(Category::Other, 0));
+        let cancelled = crate::Campaigns::<Test>::get(1).unwrap();
+        assert_eq!(cancelled.deposit, 0);
+        assert_eq!(cancelled.contributors, 1);
+        assert_eq!(crate::CampaignRefunds::<Test>::get(1), 100);
+        assert!(crate::DelistedCampaigns::<Test>::contains_key(1));
+        assert!(!crate::CampaignsByCategory::<Test>::contains_key(Category::Other, 1));
+        assert_eq!(crate::CampaignsByOwner::<Test>::iter_key_prefix(1).count(), 2);
+        assert_eq!(crate::ActiveCampaigns::<Test>::get().into_inner(), vec![0]);
+        assert_eq!(crate::Parameters::<Test>::get(), OldParameters::get());
+        assert_eq!(ProjectRegistry::on_chain_storage_version(), StorageVersion::new(1));
+        ProjectRegistry::do_try_state().expect("migrated storage satisfies the invariants");
+    });
+}
+
+#[test]
+fn migrate_v0_to_v1_is_bounded_per_step() {
+    use frame_support::{
+        migrations::{SteppedMigration, SteppedMigrationError},
+        traits::{GetStorageVersion, StorageVersion},
+        weights::WeightMeter,
+    };
+
+    new_test_ext().execute_with(|| {
+        // Arrange
+        StorageVersion::new(0).put::<ProjectRegistry>();
+        put_v0_campaigns();
+        let required = <Test as frame_system::Config>::DbWeight::get().reads_writes(4, 6);
+
+        // Act & Assert: a step needs room for one campaign or contribution
+        assert!(matches!(
+            MigrateV0ToV1::step(None, &mut WeightMeter::with_limit(required / 2)),
+            Err(SteppedMigrationError::InsufficientWeight { required: r }) if r == required
+        ));
+
+        // Act: two campaigns, the switch to contributions, three contributions and the finish
+        let mut cursor = None;
+        let mut steps = 0;
+        loop {
+            cursor = MigrateV0ToV1::step(cursor, &mut WeightMeter::with_limit(required)).unwrap();
+            steps += 1;
+            if cursor.is_none() {
+                break;
+            }
+            assert_eq!(ProjectRegistry::on_chain_storage_version(), StorageVersion::new(0));
+        }
+
+        // Assert
+        assert_eq!(steps, 7);
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().contributors, 2);
+        assert_eq!(ProjectRegistry::on_chain_storage_version(), StorageVersion::new(1));
+
+        // Act & Assert: once migrated, the migration is a no-op
+        assert!(matches!(MigrateV0ToV1::step(None, &mut WeightMeter::with_limit(required)), Ok(None)));
+        assert_eq!(crate::Campaigns::<Test>::get(0).unwrap().contributors, 2);
+    });
+}
+
+#[test]
+fn try_state_detects_unbacked_contributions() {
+    new_test_ext().execute_with(|| {
+        let (owner, contributor) = (1, 2);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        let _ = Balances::deposit_creating(&contributor, 1000);
+
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 200, None, false));
+        assert_ok!(ProjectRegistry::do_try_state());
+
+        // Release the contribution behind the pallet's back
+        Balances::unreserve(&contributor, 200);
+        assert!(ProjectRegistry::do_try_state().is_err());
+
+        // A campaign missing from the active set is caught as well
+        Balances::reserve(&contributor, 200).unwrap();
+        crate::ActiveCampaigns::<Test>::kill();
+        assert!(ProjectRegistry::do_try_state().is_err());
+    });
+}
+
+#[test]
+fn try_state_detects_untracked_refunds() {
+    new_test_ext().execute_with(|| {
+        let (owner, contributor) = (1, 2);
+        System::set_block_number(1);
+        Timestamp::set_timestamp(100);
+        let _ = Balances::deposit_creating(&owner, 1000);
+        let _ = Balances::deposit_creating(&contributor, 1000);
+
+        assert_ok!(ProjectRegistry::create_campaign(
+            RuntimeOrigin::signed(owner),
+            round_metadata(),
+            50,
+            300,
+            100,
+            1000,
+            Default::default(),
+            Default::default(),
+            FundingModel::AllOrNothing,
+        ));
+        assert_ok!(ProjectRegistry::contribute(RuntimeOrigin::signed(contributor), 0, 200, None, false));
+        assert_ok!(ProjectRegistry::cancel_campaign(RuntimeOrigin::signed(owner), 0));
+        assert_ok!(ProjectRegistry::claim_refund(RuntimeOrigin::signed(contributor), 0));
+        assert_ok!(ProjectRegistry::do_try_state());
+
+        // A refund that bypassed `claim_refund` leaves `matched` unaccounted for, even though the
+        // campaign is cancelled
+        crate::CampaignRefunds::<Test>::remove(0);
+        assert!(ProjectRegistry::do_try_state().is_err());
+    });
+}
+
+const FUZZ_ACCOUNTS: u64 = 4;
+const FUZZ_ENDOWMENT: u64 = 1_000;
+
+#[derive(Clone, Debug)]
+enum Action {
+    Create {
+        owner: u64,
+        delay: u64,
+        duration: u64,
+        soft_cap: u64,
+        hard_cap: u64,
+        funding_model: FundingModel,
+    },
+    Contribute { who: u64, campaign_id: u32, amount: u64, allow_partial: bool },
+    Cancel { who: u64, campaign_id: u32 },
+    Pause { who: u64, campaign_id: u32 },
+    Resume { who: u64, campaign_id: u32, extend_end: bool },
+    AdvanceTime { by: u64 },
+    ClaimRefund { who: u64, campaign_id: u32 },
+    SettleBatch { who: u64, campaign_id: u32, limit: u32 },
+    Challenge { who: u64, campaign_id: u32 },
+    ResolveChallenge { campaign_id: u32, uphold: bool },
+}
+
+fn funding_model() -> impl Strategy<Value = FundingModel> {
+    prop_oneof![
+        Just(FundingModel::AllOrNothing),
+        Just(FundingModel::KeepItAll),
+        (1u32..=100).prop_map(|percent| {
+            FundingModel::Threshold(sp_runtime::Perbill::from_percent(percent))
+        }),
+    ]
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    let account = 1..=FUZZ_ACCOUNTS;
+    let campaign_id = 0u32..6;
+    prop_oneof![
+        (account.clone(), 0u64..50, 1u64..200, 1u64..500, 0u64..500, funding_model()).prop_map(
+            |(owner, delay, duration, soft_cap, extra, funding_model)| Action::Create {
+                owner,
+                delay,
+                duration,
+                soft_cap,
+                hard_cap: soft_cap + extra,
+                funding_model,
+            }
+        ),
+        (account.clone(), campaign_id.clone(), 0u64..400, any::<bool>()).prop_map(
+            |(who, campaign_id, amount, allow_partial)| Action::Contribute {
+                who,
+                campaign_id,
+                amount,
+                allow_partial,
+            }
+        ),
+        (account.clone(), campaign_id.clone())
+            .prop_map(|(who, campaign_id)| Action::Cancel { who, campaign_id }),
+        (account.clone(), campaign_id.clone())
+            .prop_map(|(who, campaign_id)| Action::Pause { who, campaign_id }),
+        (account.clone(), campaign_id.clone(), any::<bool>()).prop_map(
+            |(who, campaign_id, extend_end)| Action::Resume { who, campaign_id, extend_end }
+        ),
+        (1u64..100).prop_map(|by| Action::AdvanceTime { by }),
+        (account.clone(), campaign_id.clone())
+            .prop_map(|(who, campaign_id)| Action::ClaimRefund { who, campaign_id }),
+        (account.clone(), campaign_id.clone(), 0u32..4).prop_map(
+            |(who, campaign_id, limit)| Action::SettleBatch { who, campaign_id, limit }
+        ),
+        (account, campaign_id.clone())
+            .prop_map(|(who, campaign_id)| Action::Challenge { who, campaign_id }),
+        (campaign_id, any::<bool>())
+            .prop_map(|(campaign_id, uphold)| Action::ResolveChallenge { campaign_id, uphold }),
+    ]
+}
+
+/// Apply `action`. Failing calls roll back their storage changes.
+fn apply(action: Action) -> sp_runtime::DispatchResult {
+    match action {
+        Action::Create { owner, delay, duration, soft_cap, hard_cap, funding_model } => {
+            let start = Timestamp::get() + delay;
+            ProjectRegistry::create_campaign(
+                RuntimeOrigin::signed(owner),
+                round_metadata(),
+                start,
+                start + duration,
+                soft_cap,
+                hard_cap,
+                Default::default(),
+                Default::default(),
+                funding_model,
+            )
+        },
+        Action::Contribute { who, campaign_id, amount, allow_partial } => ProjectRegistry::contribute(
+            RuntimeOrigin::signed(who),
+            campaign_id,
+            amount,
+            None,
+            allow_partial,
+        ),
+        Action::Cancel { who, campaign_id } =>
+            ProjectRegistry::cancel_campaign(RuntimeOrigin::signed(who), campaign_id),
+        Action::Pause { who, campaign_id } =>
+            ProjectRegistry::pause_campaign(RuntimeOrigin::signed(who), campaign_id),
+        Action::Resume { who, campaign_id, extend_end } =>
+            ProjectRegistry::resume_campaign(RuntimeOrigin::signed(who), campaign_id, extend_end),
+        Action::AdvanceTime { by } => {
+            let block = System::block_number() + 1;
+            System::set_block_number(block);
+            Timestamp::set_timestamp(Timestamp::get() + by);
+            ProjectRegistry::on_initialize(block);
+            Ok(())
+        },
+        Action::ClaimRefund { who, campaign_id } =>
+            ProjectRegistry::claim_refund(RuntimeOrigin::signed(who), campaign_id),
+        Action::SettleBatch { who, campaign_id, limit } =>
+            ProjectRegistry::settle_batch(RuntimeOrigin::signed(who), campaign_id, limit),
+        Action::Challenge { who, campaign_id } => ProjectRegistry::challenge_campaign(
+            RuntimeOrigin::signed(who),
+            campaign_id,
+            Default::default(),
+        ),
+        Action::ResolveChallenge { campaign_id, uphold } =>
+            ProjectRegistry::resolve_challenge(RuntimeOrigin::root(), campaign_id, uphold),
+    }
+}
+
+fn valid_transition(from: Option<&CampaignStatus>, to: &CampaignStatus) -> bool {
+    use CampaignStatus::*;
+    match (from, to) {
+        (None, Upcoming | Active) => true,
+        (Some(Upcoming), Upcoming | Active | Cancelled) => true,
+        (Some(Active), Active | Success | Failed | Cancelled | Paused) => true,
+        // A campaign whose pause times out after its end is resumed and finalized in one block
+        (Some(Paused), Paused | Active | Success | Failed | Cancelled) => true,
+        // Upheld challenges cancel finished campaigns until their settlement starts
+        (Some(Success | Failed), Cancelled | Settled) => true,
+        (Some(Cancelled), Settled) => true,
+        (Some(from), to) => from == to,
+        (None, _) => false,
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(128))]
+
+    #[test]
+    fn campaign_state_machine_conserves_balances(
+        actions in prop::collection::vec(action(), 1..60)
+    ) {
+        build_and_execute(|| {
+            System::set_block_number(1);
+            Timestamp::set_timestamp(100);
+            for who in 1..=FUZZ_ACCOUNTS {
+                let _ = Balances::deposit_creating(&who, FUZZ_ENDOWMENT);
+            }
+            let mut issuance = Balances::total_issuance();
+            let mut statuses = alloc::collections::BTreeMap::new();
+            let total = |who| Balances::free_balance(who) + Balances::reserved_balance(who);
+
+            for (step, action) in actions.into_iter().enumerate() {
+                let before: Vec<u64> = (1..=FUZZ_ACCOUNTS).map(total).collect();
+                // Dismissed challenges burn the bond, the only funds leaving the accounts
+                let bond = match action {
+                    Action::ResolveChallenge { campaign_id, uphold: false } =>
+                        crate::Challenges::<Test>::get(campaign_id).map_or(0, |challenge| challenge.bond),
+                    _ => 0,
+                };
+                if apply(action.clone()).is_ok() {
+                    issuance -= bond;
+                }
+
+                assert_eq!(Balances::total_issuance(), issuance, "step {step}: {action:?}");
+                assert_eq!(
+                    (1..=FUZZ_ACCOUNTS).map(total).sum::<u64>(),
+                    issuance,
+                    "step {step}: {action:?} moved funds out of the fuzzed accounts",
+                );
+                // Only settlement and challenge resolution move funds between accounts
+                if !matches!(action, Action::SettleBatch { .. } | Action::ResolveChallenge { .. }) {
+                    for who in 1..=FUZZ_ACCOUNTS {
+                        assert_eq!(
+                            total(who),
+                            before[who as usize - 1],
+                            "step {step}: {action:?} changed the balance of {who}",
+                        );
+                    }
+                }
+
+                for (campaign_id, campaign) in crate::Campaigns::<Test>::iter() {
+                    let previous = statuses.insert(campaign_id, campaign.status.clone());
+                    assert!(
+                        valid_transition(previous.as_ref(), &campaign.status),
+                        "step {step}: {action:?} moved campaign {campaign_id} from {previous:?} to {:?}",
+                        campaign.status,
+                    );
+                }
+                assert_eq!(crate::Campaigns::<Test>::iter().count(), statuses.len());
+
+                if let Err(e) = ProjectRegistry::do_try_state() {
+                    panic!("step {step}: {action:?} broke an invariant: {e:?}");
+                }
+            }
+        });
+    }
+}
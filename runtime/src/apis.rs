@@ -46,8 +46,8 @@ use sp_version::RuntimeVersion;
 // Local module imports
 use super::{
 	AccountId, Balance, Block, ConsensusHook, Executive, InherentDataExt, Nonce, ParachainSystem,
-	Runtime, RuntimeCall, RuntimeGenesisConfig, SessionKeys, System, TransactionPayment,
-	SLOT_DURATION, VERSION,
+	ProjectRegistry, Runtime, RuntimeCall, RuntimeGenesisConfig, SessionKeys, System,
+	TransactionPayment, SLOT_DURATION, VERSION,
 };
 
 // we move some impls outside so we can easily use them with `docify`.
@@ -213,6 +213,39 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_project_registry::runtime_api::ProjectRegistryApi<Block, AccountId, Balance> for Runtime {
+		fn fulfilment_list(
+			campaign_id: pallet_project_registry::CampaignId,
+		) -> Vec<(AccountId, pallet_project_registry::TierId, Balance)> {
+			ProjectRegistry::fulfilment_list(campaign_id)
+		}
+
+		fn reached_stretch_goals(
+			campaign_id: pallet_project_registry::CampaignId,
+		) -> Vec<(Balance, Vec<u8>)> {
+			ProjectRegistry::reached_stretch_goals(campaign_id)
+				.into_iter()
+				.map(|goal| (goal.target, goal.description.into_inner()))
+				.collect()
+		}
+
+		fn campaigns_by_category(
+			category: pallet_project_registry::Category,
+		) -> Vec<pallet_project_registry::CampaignId> {
+			ProjectRegistry::campaigns_by_category(category)
+		}
+
+		fn campaigns_by_tag(
+			tag: pallet_project_registry::TagId,
+		) -> Vec<pallet_project_registry::CampaignId> {
+			ProjectRegistry::campaigns_by_tag(tag)
+		}
+
+		fn campaigns_by_owner(owner: AccountId) -> Vec<pallet_project_registry::CampaignId> {
+			ProjectRegistry::campaigns_by_owner(&owner)
+		}
+	}
+
 	impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
 		fn collect_collation_info(header: &<Block as BlockT>::Header) -> cumulus_primitives_core::CollationInfo {
 			ParachainSystem::collect_collation_info(header)
@@ -0,0 +1,51 @@
+//! Custom origins for governance interventions.
+
+pub use pallet_custom_origins::*;
+
+use polkadot_sdk::*;
+
+#[frame_support::pallet]
+pub mod pallet_custom_origins {
+	use polkadot_sdk::frame_support::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config: polkadot_sdk::frame_system::Config {}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[derive(PartialEq, Eq, Clone, MaxEncodedLen, Encode, Decode, TypeInfo, RuntimeDebug)]
+	#[pallet::origin]
+	pub enum Origin {
+		/// Origin able to create rounds, appoint curators, cancel campaigns and set the
+		/// project registry parameters.
+		RegistryAdmin,
+		/// Origin able to approve and reject applications to any registry round.
+		RoundCurator,
+	}
+
+	macro_rules! decl_unit_ensures {
+		( $name:ident ) => {
+			pub struct $name;
+			impl<O: Into<Result<Origin, O>> + From<Origin>> EnsureOrigin<O> for $name {
+				type Success = ();
+				fn try_origin(o: O) -> Result<Self::Success, O> {
+					o.into().and_then(|o| match o {
+						Origin::$name => Ok(()),
+						r => Err(O::from(r)),
+					})
+				}
+				#[cfg(feature = "runtime-benchmarks")]
+				fn try_successful_origin() -> Result<O, ()> {
+					Ok(O::from(Origin::$name))
+				}
+			}
+		};
+		( $name:ident, $( $rest:tt )* ) => {
+			decl_unit_ensures! { $name }
+			decl_unit_ensures! { $( $rest )* }
+		};
+		() => {}
+	}
+	decl_unit_ensures!(RegistryAdmin, RoundCurator);
+}
@@ -0,0 +1,87 @@
+//! Track configurations for governance.
+
+use super::*;
+
+const fn percent(x: i32) -> sp_runtime::FixedI64 {
+	sp_runtime::FixedI64::from_rational(x as u128, 100)
+}
+use pallet_referenda::Curve;
+
+const APP_ROOT: Curve = Curve::make_reciprocal(4, 14, percent(80), percent(50), percent(100));
+const SUP_ROOT: Curve = Curve::make_linear(14, 14, percent(0), percent(50));
+const APP_REGISTRY_ADMIN: Curve = Curve::make_linear(7, 7, percent(50), percent(100));
+const SUP_REGISTRY_ADMIN: Curve =
+	Curve::make_reciprocal(5, 7, percent(1), percent(0), percent(50));
+const APP_ROUND_CURATOR: Curve = Curve::make_linear(3, 3, percent(50), percent(100));
+const SUP_ROUND_CURATOR: Curve =
+	Curve::make_reciprocal(2, 3, percent(1), percent(0), percent(25));
+
+const TRACKS_DATA: [(u16, pallet_referenda::TrackInfo<Balance, BlockNumber>); 3] = [
+	(
+		0,
+		pallet_referenda::TrackInfo {
+			name: "root",
+			max_deciding: 1,
+			decision_deposit: 100 * UNIT,
+			prepare_period: 2 * HOURS,
+			decision_period: 14 * DAYS,
+			confirm_period: DAYS,
+			min_enactment_period: DAYS,
+			min_approval: APP_ROOT,
+			min_support: SUP_ROOT,
+		},
+	),
+	(
+		1,
+		pallet_referenda::TrackInfo {
+			name: "registry_admin",
+			max_deciding: 10,
+			decision_deposit: 10 * UNIT,
+			prepare_period: HOURS,
+			decision_period: 7 * DAYS,
+			confirm_period: 3 * HOURS,
+			min_enactment_period: 10 * MINUTES,
+			min_approval: APP_REGISTRY_ADMIN,
+			min_support: SUP_REGISTRY_ADMIN,
+		},
+	),
+	(
+		2,
+		pallet_referenda::TrackInfo {
+			name: "round_curator",
+			max_deciding: 50,
+			decision_deposit: UNIT,
+			prepare_period: 10 * MINUTES,
+			decision_period: 3 * DAYS,
+			confirm_period: HOURS,
+			min_enactment_period: MINUTES,
+			min_approval: APP_ROUND_CURATOR,
+			min_support: SUP_ROUND_CURATOR,
+		},
+	),
+];
+
+pub struct TracksInfo;
+impl pallet_referenda::TracksInfo<Balance, BlockNumber> for TracksInfo {
+	type Id = u16;
+	type RuntimeOrigin = <RuntimeOrigin as frame_support::traits::OriginTrait>::PalletsOrigin;
+	fn tracks() -> &'static [(Self::Id, pallet_referenda::TrackInfo<Balance, BlockNumber>)] {
+		&TRACKS_DATA[..]
+	}
+	fn track_for(id: &Self::RuntimeOrigin) -> Result<Self::Id, ()> {
+		if let Ok(system_origin) = frame_system::RawOrigin::try_from(id.clone()) {
+			match system_origin {
+				frame_system::RawOrigin::Root => Ok(0),
+				_ => Err(()),
+			}
+		} else if let Ok(custom_origin) = origins::Origin::try_from(id.clone()) {
+			match custom_origin {
+				origins::Origin::RegistryAdmin => Ok(1),
+				origins::Origin::RoundCurator => Ok(2),
+			}
+		} else {
+			Err(())
+		}
+	}
+}
+pallet_referenda::impl_tracksinfo_get!(TracksInfo, Balance, BlockNumber);
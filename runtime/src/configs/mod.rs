@@ -23,6 +23,8 @@
 //
 // For more information, please refer to <http://unlicense.org>
 
+pub mod governance;
+mod receipts;
 mod xcm_config;
 
 use polkadot_sdk::{staging_parachain_info as parachain_info, staging_xcm as xcm, *};
@@ -37,14 +39,16 @@ use frame_support::{
 	dispatch::DispatchClass,
 	parameter_types,
 	traits::{
-		ConstBool, ConstU32, ConstU64, ConstU8, EitherOfDiverse, TransformOrigin, VariantCountOf,
+		tokens::{pay::PayFromAccount, UnityAssetBalanceConversion},
+		AsEnsureOriginWithArg, ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, EitherOf,
+		EitherOfDiverse, MapSuccess, NeverEnsureOrigin, TransformOrigin, VariantCountOf,
 	},
 	weights::{ConstantMultiplier, Weight},
 	PalletId,
 };
 use frame_system::{
 	limits::{BlockLength, BlockWeights},
-	EnsureRoot,
+	EnsureRoot, EnsureRootWithSuccess,
 };
 use pallet_xcm::{EnsureXcm, IsVoiceOfBody};
 use parachains_common::message_queue::{NarrowOriginToSibling, ParaIdToSibling};
@@ -52,7 +56,10 @@ use polkadot_runtime_common::{
 	xcm_sender::NoPriceForMessageDelivery, BlockHashCount, SlowAdjustingFeeUpdate,
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_runtime::Perbill;
+use sp_runtime::{
+	traits::{IdentityLookup, Replace, Verify},
+	Perbill, Permill,
+};
 use sp_version::RuntimeVersion;
 use xcm::latest::prelude::BodyId;
 
@@ -60,11 +67,14 @@ use xcm::latest::prelude::BodyId;
 use super::{
 	weights::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight},
 	AccountId, Aura, Balance, Balances, Block, BlockNumber, CollatorSelection, ConsensusHook, Hash,
-	MessageQueue, Nonce, PalletInfo, ParachainSystem, Runtime, RuntimeCall, RuntimeEvent,
+	MessageQueue, MultiBlockMigrations, Nonce, PalletInfo, ParachainSystem, Runtime, RuntimeCall, RuntimeEvent,
 	RuntimeFreezeReason, RuntimeHoldReason, RuntimeOrigin, RuntimeTask, Session, SessionKeys,
-	System, WeightToFee, XcmpQueue, AVERAGE_ON_INITIALIZE_RATIO, EXISTENTIAL_DEPOSIT, HOURS,
-	MAXIMUM_BLOCK_WEIGHT, MICRO_UNIT, NORMAL_DISPATCH_RATIO, SLOT_DURATION, VERSION,
+	Signature, System, Timestamp, Treasury, WeightToFee, XcmpQueue, AVERAGE_ON_INITIALIZE_RATIO, DAYS,
+	EXISTENTIAL_DEPOSIT, HOURS, MAXIMUM_BLOCK_WEIGHT, MICRO_UNIT, NORMAL_DISPATCH_RATIO,
+	SLOT_DURATION, UNIT, VERSION,
 };
+use governance::{RegistryAdmin, RegistryAdminOrigin, RoundCuratorOrigin};
+use receipts::NftReceipts;
 use xcm_config::{RelayLocation, XcmOriginToTransactDispatchOrigin};
 
 parameter_types! {
@@ -127,6 +137,33 @@ impl frame_system::Config for Runtime {
 	/// The action to take on a Runtime Upgrade
 	type OnSetCode = cumulus_pallet_parachain_system::ParachainSetCode<Self>;
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
+	/// Multi-block migrations run before any transaction is applied.
+	type MultiBlockMigrator = MultiBlockMigrations;
+}
+
+parameter_types! {
+	/// Multi-block migrations may use up to 80% of a block.
+	pub MbmServiceWeight: Weight = Perbill::from_percent(80) * RuntimeBlockWeights::get().max_block;
+}
+
+impl pallet_migrations::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	#[cfg(not(feature = "runtime-benchmarks"))]
+	type Migrations = (
+		pallet_project_registry::migrations::v1::LazyMigrationV0ToV1<
+			Runtime,
+			RegistryInitialParameters,
+			RegistryV0CampaignDeposit,
+		>,
+	);
+	#[cfg(feature = "runtime-benchmarks")]
+	type Migrations = pallet_migrations::mock_helpers::MockedMigrations;
+	type CursorMaxLen = ConstU32<65_536>;
+	type IdentifierMaxLen = ConstU32<256>;
+	type MigrationStatusHandler = ();
+	type FailedMigrationHandler = frame_support::migrations::FreezeChainOnFailedMigration;
+	type MaxServiceWeight = MbmServiceWeight;
+	type WeightInfo = pallet_migrations::weights::SubstrateWeight<Runtime>;
 }
 
 impl pallet_timestamp::Config for Runtime {
@@ -180,12 +217,50 @@ impl pallet_transaction_payment::Config for Runtime {
 	type WeightInfo = ();
 }
 
-impl pallet_sudo::Config for Runtime {
+parameter_types! {
+	pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
+	pub const SpendPeriod: BlockNumber = 6 * DAYS;
+	pub const Burn: Permill = Permill::zero();
+	pub const PayoutPeriod: BlockNumber = 30 * DAYS;
+	pub const MaxBalance: Balance = Balance::MAX;
+	/// Largest total `registry_admin` referenda can spend on matching pools per
+	/// `RegistryTreasurySpendPeriod`.
+	pub const RegistryAdminMaxSpend: Balance = 100_000 * UNIT;
+	/// Treasury spends into matching pools are limited per 30 days.
+	pub const RegistryTreasurySpendPeriod: u64 = 30 * 24 * 60 * 60 * 1000;
+	pub TreasuryAccount: AccountId = Treasury::account_id();
+}
+
+impl pallet_treasury::Config for Runtime {
+	type PalletId = TreasuryPalletId;
+	type Currency = Balances;
+	type RejectOrigin = EnsureRoot<AccountId>;
 	type RuntimeEvent = RuntimeEvent;
-	type RuntimeCall = RuntimeCall;
-	type WeightInfo = ();
+	type SpendPeriod = SpendPeriod;
+	type Burn = Burn;
+	type BurnDestination = ();
+	type SpendFunds = ();
+	type WeightInfo = pallet_treasury::weights::SubstrateWeight<Runtime>;
+	type MaxApprovals = ConstU32<100>;
+	type SpendOrigin = EnsureRootWithSuccess<AccountId, MaxBalance>;
+	type AssetKind = ();
+	type Beneficiary = AccountId;
+	type BeneficiaryLookup = IdentityLookup<AccountId>;
+	type Paymaster = PayFromAccount<Balances, TreasuryAccount>;
+	type BalanceConverter = UnityAssetBalanceConversion;
+	type PayoutPeriod = PayoutPeriod;
+	type BlockNumberProvider = System;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
 }
 
+/// Treasury spends into registry matching pools: unlimited for root, capped for the
+/// `registry_admin` track per `RegistryTreasurySpendPeriod`.
+pub type RegistryTreasurySpendOrigin = EitherOf<
+	EnsureRootWithSuccess<AccountId, MaxBalance>,
+	MapSuccess<RegistryAdmin, Replace<RegistryAdminMaxSpend>>,
+>;
+
 parameter_types! {
 	pub const ReservedXcmpWeight: Weight = MAXIMUM_BLOCK_WEIGHT.saturating_div(4);
 	pub const ReservedDmpWeight: Weight = MAXIMUM_BLOCK_WEIGHT.saturating_div(4);
@@ -321,7 +396,43 @@ parameter_types! {
 	pub const MaxDescLen: u32 = 1000;
 	pub const MaxLinkLen: u32 = 200;
 	pub const MaxActiveCampaigns: u32 = 100;
-	pub const CampaignDeposit: Balance = EXISTENTIAL_DEPOSIT * 10;
+	pub const RegistryPalletId: PalletId = PalletId(*b"py/prreg");
+	/// Applications close one day before a round starts.
+	pub const RoundApplicationCutoff: u64 = 24 * 60 * 60 * 1000;
+	pub const MaxRoundCampaigns: u32 = 100;
+	/// Settled campaigns are kept for 30 days before they can be reaped.
+	pub const CampaignRetentionPeriod: u64 = 30 * 24 * 60 * 60 * 1000;
+	pub const MaxReapBatch: u32 = 500;
+	pub const MaxRegistryMessageLen: u32 = 512;
+	/// Anti-collusion rounds can be finalized without a tally 14 days after they end.
+	pub const RoundTallyTimeout: u64 = 14 * 24 * 60 * 60 * 1000;
+	pub const MaxRoundSignups: u32 = 1000;
+	pub const MaxRewardTiers: u32 = 10;
+	pub const MaxStretchGoals: u32 = 5;
+	pub const MaxCampaignTags: u32 = 8;
+	/// Campaigns reserve ten times the existential deposit plus one micro-UNIT per metadata byte.
+	pub const CampaignDepositBase: Balance = EXISTENTIAL_DEPOSIT * 10;
+	pub const CampaignDepositPerByte: Balance = MICRO_UNIT;
+	pub const MaxMetadataRevisions: u32 = 20;
+	/// Campaigns can be extended by up to 14 days in total.
+	pub const MaxCampaignExtension: u64 = 14 * 24 * 60 * 60 * 1000;
+	/// Paused campaigns resume on their own after 7 days.
+	pub const MaxCampaignPause: u64 = 7 * 24 * 60 * 60 * 1000;
+	/// Successful campaigns are paid out 3 days after they end, leaving governance time to
+	/// force-cancel them.
+	pub const CampaignPayoutDelay: u64 = 3 * 24 * 60 * 60 * 1000;
+	/// Deposit version 0 of the registry reserved per campaign, the former compile-time
+	/// `CampaignDeposit`.
+	pub const RegistryV0CampaignDeposit: Balance = EXISTENTIAL_DEPOSIT * 10;
+	/// Registry parameters set at genesis and seeded by the v1 migration.
+	pub RegistryInitialParameters: pallet_project_registry::ParametersOf<Runtime> =
+		pallet_project_registry::RegistryParameters {
+			min_contribution: EXISTENTIAL_DEPOSIT,
+			match_cap: Perbill::from_percent(25),
+			challenge_bond: EXISTENTIAL_DEPOSIT * 50,
+			success_fee: Permill::from_percent(2),
+			settlement_bounty: EXISTENTIAL_DEPOSIT / 10,
+		};
 }
 
 impl pallet_project_registry::Config for Runtime {
@@ -332,5 +443,75 @@ impl pallet_project_registry::Config for Runtime {
 	type MaxDescLen = MaxDescLen;
 	type MaxLinkLen = MaxLinkLen;
 	type MaxActive = MaxActiveCampaigns;
-	type MinimumDeposit = CampaignDeposit;
+	type ParameterOrigin = RegistryAdminOrigin;
+	type AdminOrigin = RegistryAdminOrigin;
+	type Slashed = Treasury;
+	type OnSuccessFee = Treasury;
+	type TreasuryAccount = TreasuryAccount;
+	type TreasurySpendOrigin = RegistryTreasurySpendOrigin;
+	type TreasurySpendPeriod = RegistryTreasurySpendPeriod;
+	type RoundCuratorOrigin = RoundCuratorOrigin;
+	type PalletId = RegistryPalletId;
+	type ApplicationCutoff = RoundApplicationCutoff;
+	type MaxRoundCampaigns = MaxRoundCampaigns;
+	type RetentionPeriod = CampaignRetentionPeriod;
+	type MaxReapBatch = MaxReapBatch;
+	// No proof system is wired in yet, so rounds cannot be coordinated.
+	type TallyVerifier = ();
+	type MaxMessageLen = MaxRegistryMessageLen;
+	type TallyTimeout = RoundTallyTimeout;
+	type MaxSignups = MaxRoundSignups;
+	type OnContribution = NftReceipts;
+	type MaxTiers = MaxRewardTiers;
+	type MaxStretchGoals = MaxStretchGoals;
+	type MaxExtension = MaxCampaignExtension;
+	type MaxPauseDuration = MaxCampaignPause;
+	type MaxTags = MaxCampaignTags;
+	type DepositBase = CampaignDepositBase;
+	type DepositPerByte = CampaignDepositPerByte;
+	type MaxRevisions = MaxMetadataRevisions;
+	type MetadataValidator = ();
+	type PayoutDelay = CampaignPayoutDelay;
+}
+
+parameter_types! {
+	/// Receipts are only issued by the registry, so trading and approvals are switched off.
+	pub ReceiptFeatures: pallet_nfts::PalletFeatures = pallet_nfts::PalletFeatures::from_disabled(
+		pallet_nfts::PalletFeature::Trading |
+			pallet_nfts::PalletFeature::Approvals |
+			pallet_nfts::PalletFeature::Swaps,
+	);
+	pub const ReceiptMetadataDepositBase: Balance = 10 * MICRO_UNIT;
+	pub const ReceiptDepositPerByte: Balance = MICRO_UNIT;
+}
+
+/// Holds the contribution receipts of the project registry. Only the registry creates
+/// collections, for free, as it pays for every receipt it issues.
+impl pallet_nfts::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type CollectionId = pallet_project_registry::CampaignId;
+	type ItemId = u128;
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<NeverEnsureOrigin<AccountId>>;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type Locker = ();
+	type CollectionDeposit = ConstU128<0>;
+	type ItemDeposit = ConstU128<0>;
+	type MetadataDepositBase = ReceiptMetadataDepositBase;
+	type AttributeDepositBase = ReceiptMetadataDepositBase;
+	type DepositPerByte = ReceiptDepositPerByte;
+	type StringLimit = ConstU32<256>;
+	type KeyLimit = ConstU32<32>;
+	type ValueLimit = ConstU32<64>;
+	type ApprovalsLimit = ConstU32<0>;
+	type ItemAttributesApprovalsLimit = ConstU32<0>;
+	type MaxTips = ConstU32<0>;
+	type MaxDeadlineDuration = ConstU32<{ 30 * DAYS }>;
+	type MaxAttributesPerCall = ConstU32<10>;
+	type Features = ReceiptFeatures;
+	type OffchainSignature = Signature;
+	type OffchainPublic = <Signature as Verify>::Signer;
+	type WeightInfo = pallet_nfts::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type Helper = ();
 }
@@ -0,0 +1,89 @@
+//! Soulbound NFT receipts for project registry contributions.
+
+use codec::Encode;
+use frame_support::{
+	storage::with_storage_layer,
+	traits::tokens::nonfungibles_v2::{Create, Inspect, Mutate},
+	weights::Weight,
+};
+use pallet_nfts::{
+	CollectionConfigFor, CollectionSettings, ItemConfig, ItemSetting, ItemSettings, WeightInfo,
+};
+use pallet_project_registry::{CampaignId, OnContribution, RoundId};
+use polkadot_sdk::*;
+use sp_runtime::DispatchResult;
+
+use crate::{AccountId, Balance, Nfts, ProjectRegistry, Runtime};
+
+/// Issues one non-transferable `pallet_nfts` item per contributor in a collection per campaign.
+///
+/// The collection id is the campaign id and the collection is owned by the registry account.
+/// The item id is derived from the contributor's account, and its `amount` and `round`
+/// attributes are updated with every contribution.
+pub struct NftReceipts;
+
+impl NftReceipts {
+	fn item(who: &AccountId) -> u128 {
+		u128::from_le_bytes(sp_io::hashing::blake2_128(who.as_ref()))
+	}
+
+	fn issue(
+		campaign_id: CampaignId,
+		round_id: Option<RoundId>,
+		who: &AccountId,
+		total: Balance,
+	) -> DispatchResult {
+		let item = Self::item(who);
+		if <Nfts as Inspect<AccountId>>::collection_owner(&campaign_id).is_none() {
+			let registry = ProjectRegistry::account_id();
+			let config = CollectionConfigFor::<Runtime> {
+				settings: CollectionSettings::all_enabled(),
+				max_supply: None,
+				mint_settings: Default::default(),
+			};
+			Nfts::create_collection_with_id(campaign_id, &registry, &registry, &config)?;
+		}
+		if <Nfts as Inspect<AccountId>>::owner(&campaign_id, &item).is_none() {
+			let config = ItemConfig { settings: ItemSettings::from_disabled(ItemSetting::Transferable.into()) };
+			Nfts::mint_into(&campaign_id, &item, who, &config, false)?;
+		}
+		<Nfts as Mutate<AccountId, ItemConfig>>::set_attribute(
+			&campaign_id,
+			&item,
+			b"amount",
+			&total.encode(),
+		)?;
+		<Nfts as Mutate<AccountId, ItemConfig>>::set_attribute(
+			&campaign_id,
+			&item,
+			b"round",
+			&round_id.encode(),
+		)
+	}
+}
+
+impl OnContribution<AccountId, Balance> for NftReceipts {
+	fn on_contribution(
+		campaign_id: CampaignId,
+		round_id: Option<RoundId>,
+		who: &AccountId,
+		total: Balance,
+	) {
+		// A receipt that cannot be issued must not fail the contribution
+		if let Err(error) = with_storage_layer(|| Self::issue(campaign_id, round_id, who, total)) {
+			log::warn!(
+				target: "runtime::receipts",
+				"failed to issue receipt for campaign {campaign_id}: {error:?}",
+			);
+		}
+	}
+
+	/// The first receipt of a campaign creates its collection; every receipt is minted once and
+	/// has both attributes set.
+	fn weight() -> Weight {
+		type NftWeights = <Runtime as pallet_nfts::Config>::WeightInfo;
+		NftWeights::force_create()
+			.saturating_add(NftWeights::mint())
+			.saturating_add(NftWeights::set_attribute().saturating_mul(2))
+	}
+}
@@ -1,6 +1,7 @@
 use crate::{
-	AccountId, BalancesConfig, CollatorSelectionConfig, ParachainInfoConfig, PolkadotXcmConfig,
-	RuntimeGenesisConfig, SessionConfig, SessionKeys, SudoConfig, EXISTENTIAL_DEPOSIT,
+	configs::RegistryInitialParameters, AccountId, BalancesConfig, CollatorSelectionConfig,
+	ParachainInfoConfig, PolkadotXcmConfig, ProjectRegistryConfig, RuntimeGenesisConfig,
+	SessionConfig, SessionKeys, EXISTENTIAL_DEPOSIT,
 };
 
 use alloc::{vec, vec::Vec};
@@ -28,9 +29,9 @@ pub fn template_session_keys(keys: AuraId) -> SessionKeys {
 fn testnet_genesis(
 	invulnerables: Vec<(AccountId, AuraId)>,
 	endowed_accounts: Vec<AccountId>,
-	root: AccountId,
 	id: ParaId,
 ) -> Value {
+	let registry_parameters = RegistryInitialParameters::get();
 	let config = RuntimeGenesisConfig {
 		balances: BalancesConfig {
 			balances: endowed_accounts
@@ -62,7 +63,13 @@ fn testnet_genesis(
 			safe_xcm_version: Some(SAFE_XCM_VERSION),
 			..Default::default()
 		},
-		sudo: SudoConfig { key: Some(root) },
+		project_registry: ProjectRegistryConfig {
+			min_contribution: registry_parameters.min_contribution,
+			match_cap: registry_parameters.match_cap,
+			challenge_bond: registry_parameters.challenge_bond,
+			success_fee: registry_parameters.success_fee,
+			settlement_bounty: registry_parameters.settlement_bounty,
+		},
 		..Default::default()
 	};
 
@@ -77,7 +84,6 @@ fn local_testnet_genesis() -> Value {
 			(Sr25519Keyring::Bob.to_account_id(), Sr25519Keyring::Bob.public().into()),
 		],
 		Sr25519Keyring::well_known().map(|k| k.to_account_id()).collect(),
-		Sr25519Keyring::Alice.to_account_id(),
 		PARACHAIN_ID.into(),
 	)
 }
@@ -90,7 +96,6 @@ fn development_config_genesis() -> Value {
 			(Sr25519Keyring::Bob.to_account_id(), Sr25519Keyring::Bob.public().into()),
 		],
 		Sr25519Keyring::well_known().map(|k| k.to_account_id()).collect(),
-		Sr25519Keyring::Alice.to_account_id(),
 		PARACHAIN_ID.into(),
 	)
 }
@@ -38,6 +38,8 @@ pub use sp_runtime::{MultiAddress, Perbill, Permill};
 
 use weights::ExtrinsicBaseWeight;
 
+pub use configs::governance::pallet_custom_origins;
+
 /// Alias to 512-bit hash when used in the context of a transaction signature on the chain.
 pub type Signature = MultiSignature;
 
@@ -91,11 +93,22 @@ pub type TxExtension = (
 pub type UncheckedExtrinsic =
 	generic::UncheckedExtrinsic<Address, RuntimeCall, Signature, TxExtension>;
 
+frame_support::parameter_types! {
+	/// Sudo was replaced by OpenGov, its key is removed from storage.
+	pub const SudoPalletName: &'static str = "Sudo";
+}
+
 /// All migrations of the runtime, aside from the ones declared in the pallets.
 ///
-/// This can be a tuple of types, each implementing `OnRuntimeUpgrade`.
+/// This can be a tuple of types, each implementing `OnRuntimeUpgrade`. Multi-block migrations
+/// are configured in `pallet_migrations` instead.
 #[allow(unused_parens)]
-type Migrations = ();
+type Migrations = (
+	frame_support::migrations::RemovePallet<
+		SudoPalletName,
+		<Runtime as frame_system::Config>::DbWeight,
+	>,
+);
 
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
@@ -167,10 +180,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: alloc::borrow::Cow::Borrowed("parachain-template-runtime"),
 	impl_name: alloc::borrow::Cow::Borrowed("parachain-template-runtime"),
 	authoring_version: 1,
-	spec_version: 1,
+	spec_version: 2,
 	impl_version: 0,
 	apis: apis::RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 	system_version: 1,
 };
 
@@ -271,16 +284,28 @@ mod runtime {
 	pub type Timestamp = pallet_timestamp;
 	#[runtime::pallet_index(3)]
 	pub type ParachainInfo = parachain_info;
+	#[runtime::pallet_index(4)]
+	pub type MultiBlockMigrations = pallet_migrations;
 
 	// Monetary stuff.
 	#[runtime::pallet_index(10)]
 	pub type Balances = pallet_balances;
 	#[runtime::pallet_index(11)]
 	pub type TransactionPayment = pallet_transaction_payment;
+	#[runtime::pallet_index(12)]
+	pub type Treasury = pallet_treasury;
 
 	// Governance
-	#[runtime::pallet_index(15)]
-	pub type Sudo = pallet_sudo;
+	#[runtime::pallet_index(14)]
+	pub type Origins = pallet_custom_origins;
+	#[runtime::pallet_index(16)]
+	pub type Scheduler = pallet_scheduler;
+	#[runtime::pallet_index(17)]
+	pub type Preimage = pallet_preimage;
+	#[runtime::pallet_index(18)]
+	pub type ConvictionVoting = pallet_conviction_voting;
+	#[runtime::pallet_index(19)]
+	pub type Referenda = pallet_referenda;
 
 	// Collator support. The order of these 4 are important and shall not change.
 	#[runtime::pallet_index(20)]
@@ -311,6 +336,8 @@ mod runtime {
 	// Project Registry
 	#[runtime::pallet_index(51)]
 	pub type ProjectRegistry = pallet_project_registry;
+	#[runtime::pallet_index(52)]
+	pub type Nfts = pallet_nfts;
 }
 
 #[docify::export(register_validate_block)]